@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+/// The Numeric Annotation Glyphs that have a short symbol, like `$1` for `!`.
+const NAG_SYMBOLS: [(u8, &str); 6] = [
+    (1, "!"),
+    (2, "?"),
+    (3, "!!"),
+    (4, "??"),
+    (5, "!?"),
+    (6, "?!"),
+];
+
+/// How good a position is, as engines report it.
+/// Positive values are good for white on game records, and for the player that moves next on
+/// search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Evaluation {
+    /// The advantage in hundredths of a pawn.
+    Centipawns(i32),
+    /// The amount of moves until mate, negative if the other player mates.
+    Mate(i32),
+}
+
+impl std::fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Evaluation::Centipawns(centipawns) => {
+                let sign = if *centipawns < 0 { "-" } else { "" };
+                let centipawns = centipawns.unsigned_abs();
+                write!(f, "{}{}.{:02}", sign, centipawns / 100, centipawns % 100)
+            }
+            Evaluation::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+impl TryFrom<&str> for Evaluation {
+    type Error = ();
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Some(moves) = value.strip_prefix('#') {
+            return moves.parse().map(Evaluation::Mate).map_err(|_| ());
+        }
+
+        let pawns: f64 = value.parse().map_err(|_| ())?;
+        Ok(Evaluation::Centipawns((pawns * 100.0).round() as i32))
+    }
+}
+
+/// The notes a movement carries on a game record.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotations {
+    /// The comment written before the movement, only used on the first movement of a variation.
+    pub starting_comment: Option<String>,
+    /// The comment written after the movement.
+    pub comment: Option<String>,
+    /// The Numeric Annotation Glyphs of the movement, like `1` for a good move.
+    pub nags: Vec<u8>,
+    /// The milliseconds the player had left after the movement.
+    pub clock_ms: Option<u128>,
+    pub eval: Option<Evaluation>,
+}
+
+impl Annotations {
+    /// Checks if the movement carries no notes at all.
+    pub fn is_empty(&self) -> bool {
+        self == &Annotations::default()
+    }
+
+    /// Adds text to the comment after the movement, reading the `[%clk]` and `[%eval]` commands
+    /// in it.
+    pub fn add_comment(&mut self, text: &str) {
+        let text = self.read_commands(text);
+        append_text(&mut self.comment, &text);
+    }
+
+    /// Adds text to the comment before the movement, reading the `[%clk]` and `[%eval]`
+    /// commands in it.
+    pub fn add_starting_comment(&mut self, text: &str) {
+        let text = self.read_commands(text);
+        append_text(&mut self.starting_comment, &text);
+    }
+
+    /// Get's the comment after the movement as written on a PGN, with the clock and evaluation
+    /// as commands.
+    pub fn pgn_comment(&self) -> Option<String> {
+        let mut parts = vec![];
+        if let Some(clock_ms) = self.clock_ms {
+            parts.push(format!("[%clk {}]", format_clock(clock_ms)));
+        }
+        if let Some(eval) = self.eval {
+            parts.push(format!("[%eval {}]", eval));
+        }
+        if let Some(comment) = &self.comment {
+            parts.push(comment.clone());
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+
+    /// Removes the known commands from a comment, storing their values.
+    fn read_commands(&mut self, text: &str) -> String {
+        let mut rest = String::new();
+        let mut remaining = text;
+        while let Some(start) = remaining.find("[%") {
+            let Some(length) = remaining[start..].find(']') else {
+                break;
+            };
+            rest.push_str(&remaining[..start]);
+
+            let command = &remaining[start + 2..start + length];
+            let (name, value) = command.split_once(' ').unwrap_or((command, ""));
+            let value = value.trim();
+            let (clock_ms, eval) = match name {
+                "clk" => (parse_clock(value), None),
+                "eval" => (None, Evaluation::try_from(value).ok()),
+                _ => (None, None),
+            };
+            if clock_ms.is_some() {
+                self.clock_ms = clock_ms;
+            } else if eval.is_some() {
+                self.eval = eval;
+            } else {
+                rest.push_str(&remaining[start..=start + length]);
+            }
+
+            remaining = &remaining[start + length + 1..];
+        }
+        rest.push_str(remaining);
+
+        rest.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+}
+
+/// Get's the Numeric Annotation Glyph written with the given symbol, like `$3` for `!!`.
+pub fn nag_from_symbol(symbol: &str) -> Option<u8> {
+    NAG_SYMBOLS
+        .iter()
+        .find(|(_, nag_symbol)| *nag_symbol == symbol)
+        .map(|(nag, _)| *nag)
+}
+
+fn append_text(comment: &mut Option<String>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+
+    match comment {
+        Some(comment) => {
+            comment.push(' ');
+            comment.push_str(text);
+        }
+        None => *comment = Some(text.to_string()),
+    }
+}
+
+/// Reads a clock written as `h:mm:ss`, with optional fractions of a second.
+fn parse_clock(clock: &str) -> Option<u128> {
+    let mut parts = clock.rsplit(':');
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let minutes: u128 = parts
+        .next()
+        .map_or(Some(0), |minutes| minutes.parse().ok())?;
+    let hours: u128 = parts.next().map_or(Some(0), |hours| hours.parse().ok())?;
+    if parts.next().is_some() || seconds < 0.0 {
+        return None;
+    }
+
+    Some((hours * 60 + minutes) * 60 * 1000 + (seconds * 1000.0).round() as u128)
+}
+
+fn format_clock(clock_ms: u128) -> String {
+    let seconds = clock_ms / 1000;
+    let clock = format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+
+    match clock_ms % 1000 {
+        0 => clock,
+        millis => format!(
+            "{}.{}",
+            clock,
+            format!("{:03}", millis).trim_end_matches('0')
+        ),
+    }
+}
@@ -0,0 +1,160 @@
+use super::*;
+
+impl Board {
+    /// Get's the pieces of the given color that attack the given position.
+    pub fn attackers_of(&self, position: &BoardPosition, color: &PieceColors) -> Vec<ChessPiece> {
+        self.get_pieces_from(color)
+            .iter()
+            .filter(|piece| self.get_attacked_positions(piece).contains(position))
+            .cloned()
+            .collect()
+    }
+
+    /// Get's every position attacked by the pieces of the given color, without repetitions.
+    /// Cells with pieces of the same color count as attacked, since they are defended.
+    pub fn attacked_squares(&self, color: &PieceColors) -> Vec<BoardPosition> {
+        let mut positions: Vec<BoardPosition> = vec![];
+        for position in self
+            .get_pieces_from(color)
+            .iter()
+            .flat_map(|piece| self.get_attacked_positions(piece))
+        {
+            if !positions.contains(&position) {
+                positions.push(position);
+            }
+        }
+
+        positions
+    }
+
+    /// Checks if any piece of the given color attacks the given position.
+    pub fn is_attacked(&self, position: &BoardPosition, by: &PieceColors) -> bool {
+        self.get_pieces_from(by)
+            .iter()
+            .any(|piece| self.get_attacked_positions(piece).contains(position))
+    }
+
+    /// Get's the positions the piece could capture on if there were a foe piece on them.
+    /// Unlike the movement paths, pawns only attack diagonally and every path ends on the first
+    /// piece found, whatever its color.
+    pub(crate) fn get_attacked_positions(&self, piece: &ChessPiece) -> Vec<BoardPosition> {
+        get_movement_pattern(piece, self)
+            .into_iter()
+            .filter(|MovementPath { kind, .. }| kind != &MovementKind::MoveOnly)
+            .flat_map(|MovementPath { path, .. }| {
+                let mut found_piece = false;
+                path.0.into_iter().take_while(move |position| {
+                    let keep_going = !found_piece;
+                    found_piece = self.get_piece(position).is_some();
+                    keep_going
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(position: &str) -> BoardPosition {
+        position.try_into().unwrap()
+    }
+
+    fn board(diagram: &str) -> Board {
+        BoardBuilder::from_diagram(diagram)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn pawns_only_attack_diagonally() {
+        let board = board(
+            "\
+            . . . . k . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . P . . . .
+            . . . . K . . .",
+        );
+
+        let pawn = board.get_piece(&position("d2")).unwrap();
+        let mut attacked = board.get_attacked_positions(&pawn);
+        attacked.sort_by_key(|position| position.to_string());
+        assert_eq!(attacked, [position("c3"), position("e3")]);
+
+        assert!(!board.is_attacked(&position("d3"), &PieceColors::White));
+        assert!(!board.is_attacked(&position("d4"), &PieceColors::White));
+        assert!(board
+            .get_pseudo_legal_positions(&pawn)
+            .contains(&position("d4")));
+    }
+
+    #[test]
+    fn sliders_attack_up_to_the_first_piece() {
+        let board = board(
+            "\
+            . . . . k . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            R . . . K . . .",
+        );
+
+        let rook = board.get_piece(&position("a1")).unwrap();
+        let attacked = board.get_attacked_positions(&rook);
+        assert!(attacked.contains(&position("d1")));
+        assert!(attacked.contains(&position("a8")));
+        // The rook defends its own king, but doesn't see through it.
+        assert!(attacked.contains(&position("e1")));
+        assert!(!attacked.contains(&position("f1")));
+    }
+
+    #[test]
+    fn attackers_of_finds_every_attacker() {
+        let board = board(
+            "\
+            . . . . k . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . N . .
+            . . . . . . . .
+            R . . Q K . . .",
+        );
+
+        let mut attackers: Vec<PieceTypes> = board
+            .attackers_of(&position("d2"), &PieceColors::White)
+            .iter()
+            .map(|piece| *piece.kind())
+            .collect();
+        attackers.sort_by_key(PieceTypes::symbol);
+        assert_eq!(
+            attackers,
+            [PieceTypes::King, PieceTypes::Knight, PieceTypes::Queen]
+        );
+        assert_eq!(
+            board.attackers_of(&position("d2"), &PieceColors::Black),
+            vec![]
+        );
+
+        let mut attacked: Vec<String> = board
+            .attacked_squares(&PieceColors::White)
+            .iter()
+            .map(BoardPosition::to_string)
+            .collect();
+        let squares = attacked.len();
+        attacked.sort();
+        attacked.dedup();
+        assert_eq!(attacked.len(), squares);
+        assert!(attacked.contains(&"d1".to_string()));
+    }
+}
@@ -0,0 +1,5 @@
+use chess_engine::UciEngine;
+
+fn main() {
+    UciEngine::new(std::io::stdout()).run(std::io::stdin().lock());
+}
@@ -0,0 +1,172 @@
+use super::*;
+
+/// Builds a board from its pieces, deriving every other representation of it.
+/// All the methods can be chained, and the board is only checked when calling `build`.
+#[derive(Debug, Clone)]
+pub struct BoardBuilder {
+    dimensions: BoardDimensions,
+    pieces: Vec<ChessPiece>,
+    white_castling: Option<CastlingState>,
+    black_castling: Option<CastlingState>,
+    en_passant_position: Option<BoardPosition>,
+    side_to_move: PieceColors,
+    fairy_pieces: Vec<FairyPiece>,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        BoardBuilder::new(BoardDimensions::STANDARD)
+    }
+}
+
+impl From<&Board> for BoardBuilder {
+    fn from(board: &Board) -> Self {
+        BoardBuilder {
+            dimensions: board.dimensions(),
+            pieces: board
+                .cells
+                .iter()
+                .flatten()
+                .filter_map(ChessCell::piece)
+                .collect(),
+            white_castling: board.white_castling.clone(),
+            black_castling: board.black_castling.clone(),
+            en_passant_position: board.en_passant_position.clone(),
+            side_to_move: board.side_to_move.clone(),
+            fairy_pieces: board.fairy_pieces.clone(),
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Creates an empty board of the given size, without castling and with white to move.
+    pub fn new(dimensions: BoardDimensions) -> Self {
+        BoardBuilder {
+            dimensions,
+            pieces: vec![],
+            white_castling: None,
+            black_castling: None,
+            en_passant_position: None,
+            side_to_move: PieceColors::White,
+            fairy_pieces: vec![],
+        }
+    }
+
+    /// Places a piece on the given position, replacing the piece that was there.
+    pub fn place(mut self, kind: PieceTypes, color: PieceColors, position: BoardPosition) -> Self {
+        self.pieces
+            .retain(|piece| piece.board_position() != &position);
+        self.pieces.push(ChessPiece::new(kind, position, color));
+        self
+    }
+
+    /// Removes the piece on the given position, if there's one.
+    pub fn remove(mut self, position: &BoardPosition) -> Self {
+        self.pieces
+            .retain(|piece| piece.board_position() != position);
+        self
+    }
+
+    /// Sets the castling rights of the player of the given color.
+    pub fn castling(mut self, color: PieceColors, castling: Option<CastlingState>) -> Self {
+        match color {
+            PieceColors::Black => self.black_castling = castling,
+            PieceColors::White => self.white_castling = castling,
+        }
+        self
+    }
+
+    /// Sets the position of the pawn that just moved two cells and can be captured en passant.
+    pub fn en_passant(mut self, position: Option<BoardPosition>) -> Self {
+        self.en_passant_position = position;
+        self
+    }
+
+    /// Sets the color of the player that moves next.
+    pub fn side_to_move(mut self, color: PieceColors) -> Self {
+        self.side_to_move = color;
+        self
+    }
+
+    /// Sets the fairy pieces that can be placed and played on the board.
+    pub fn fairy_pieces(mut self, fairy_pieces: Vec<FairyPiece>) -> Self {
+        self.fairy_pieces = fairy_pieces;
+        self
+    }
+
+    /// Builds the board, deriving the cells, the pieces of each color, the king positions and the
+    /// check state from the placed pieces.
+    /// Returns every problem found if the resulting board is not valid.
+    pub fn build(self) -> Result<Board, Vec<BoardProblem>> {
+        let BoardBuilder {
+            dimensions,
+            pieces,
+            white_castling,
+            black_castling,
+            en_passant_position,
+            side_to_move,
+            fairy_pieces,
+        } = self;
+
+        let outside_pieces: Vec<BoardProblem> = pieces
+            .iter()
+            .filter(|piece| !dimensions.contains(piece.board_position()))
+            .map(|piece| BoardProblem::PositionOutsideBoard(piece.board_position().clone()))
+            .collect();
+        if !outside_pieces.is_empty() {
+            return Err(outside_pieces);
+        }
+
+        let mut cells = vec![vec![ChessCell::none(); dimensions.files]; dimensions.ranks];
+        for piece in &pieces {
+            let (row, column) = piece.position();
+            cells[row][column] = ChessCell::some(piece.clone());
+        }
+
+        let (white_pieces, black_pieces): (Vec<ChessPiece>, Vec<ChessPiece>) = pieces
+            .into_iter()
+            .partition(|piece| piece.color() == &PieceColors::White);
+
+        let king_position = |pieces: &[ChessPiece]| {
+            pieces
+                .iter()
+                .find(|piece| piece.kind() == &PieceTypes::King)
+                .map(|king| king.board_position().clone())
+        };
+        let (Some(white_king_position), Some(black_king_position)) =
+            (king_position(&white_pieces), king_position(&black_pieces))
+        else {
+            let missing_kings = [
+                (PieceColors::White, &white_pieces),
+                (PieceColors::Black, &black_pieces),
+            ]
+            .into_iter()
+            .filter(|(_, pieces)| king_position(pieces).is_none())
+            .map(|(color, _)| BoardProblem::MissingKing(color))
+            .collect();
+            return Err(missing_kings);
+        };
+
+        let mut board = Board::new(
+            cells,
+            black_pieces,
+            white_pieces,
+            white_king_position,
+            black_king_position,
+            None,
+            en_passant_position,
+            white_castling,
+            black_castling,
+        );
+        board.side_to_move = side_to_move.clone();
+        board.fairy_pieces = fairy_pieces;
+        board.update_check_state(&side_to_move);
+
+        let problems = board.validate();
+        if problems.is_empty() {
+            Ok(board)
+        } else {
+            Err(problems)
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use super::*;
+
+use thiserror::Error;
+
+/// The empty cell symbol of a diagram.
+const EMPTY_CELL: char = '.';
+
+#[derive(Debug, Error)]
+pub enum DiagramError {
+    #[error("The symbol `{0}` is not a piece.")]
+    InvalidSymbol(char),
+    #[error("The rows of the diagram have different lengths, or the diagram is too big.")]
+    InvalidDimensions,
+}
+
+impl BoardBuilder {
+    /// Creates a builder with the pieces drawn on the given diagram.
+    /// Each line is a rank, starting with the last one, and each symbol is a cell. White pieces are
+    /// uppercase letters, black pieces lowercase ones and empty cells are dots. Spaces are ignored,
+    /// so `r n b q k b n r` and `rnbqkbnr` are the same rank.
+    /// Any other letter is a fairy piece, which must be added to the builder with `fairy_pieces`.
+    /// The rest of the board, like castling or the side to move, can be set on the builder.
+    pub fn from_diagram(diagram: &str) -> Result<Self, DiagramError> {
+        let rows: Vec<Vec<char>> = diagram
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect::<Vec<char>>()
+            })
+            .filter(|row| !row.is_empty())
+            .collect();
+
+        let dimensions = BoardDimensions {
+            files: rows.first().map_or(0, Vec::len),
+            ranks: rows.len(),
+        };
+        let is_valid = dimensions.files <= MAX_FILES
+            && dimensions.ranks <= MAX_RANKS
+            && rows.iter().all(|row| row.len() == dimensions.files);
+        if !is_valid {
+            return Err(DiagramError::InvalidDimensions);
+        }
+
+        let mut builder = BoardBuilder::new(dimensions);
+        for (rank, row) in rows.iter().rev().enumerate() {
+            for (file, symbol) in row.iter().enumerate() {
+                if *symbol == EMPTY_CELL {
+                    continue;
+                }
+
+                let kind = match PieceTypes::from_symbol(*symbol) {
+                    Some(kind) => kind,
+                    None if symbol.is_ascii_alphabetic() => {
+                        PieceTypes::Fairy(symbol.to_ascii_uppercase())
+                    }
+                    None => return Err(DiagramError::InvalidSymbol(*symbol)),
+                };
+                let color = if symbol.is_uppercase() {
+                    PieceColors::White
+                } else {
+                    PieceColors::Black
+                };
+                let position = (rank, file)
+                    .try_into()
+                    .map_err(|_| DiagramError::InvalidDimensions)?;
+                builder = builder.place(kind, color, position);
+            }
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Prints the board as a diagram that can be read back with `BoardBuilder::from_diagram`.
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in self.cells.iter().rev() {
+            let symbols: Vec<String> = row
+                .iter()
+                .map(|cell| match cell.piece() {
+                    Some(piece) if piece.color() == &PieceColors::White => piece.kind().symbol(),
+                    Some(piece) => piece.kind().symbol().to_ascii_lowercase(),
+                    None => EMPTY_CELL,
+                })
+                .map(String::from)
+                .collect();
+            writeln!(f, "{}", symbols.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(position: &str) -> BoardPosition {
+        position.try_into().unwrap()
+    }
+
+    fn valid_positions(board: &Board, position: &str) -> Vec<BoardPosition> {
+        let piece = board.get_piece(&self::position(position)).unwrap();
+        get_valid_movements_positions(&piece, board)
+    }
+
+    #[test]
+    fn diagrams_are_printed_as_they_are_read() {
+        let diagram = "\
+            r n b q k b n r\n\
+            p p p p . p p p\n\
+            . . . . . . . .\n\
+            . . . . p . . .\n\
+            . . . . P . . .\n\
+            . . . . . N . .\n\
+            P P P P . P P P\n\
+            R N B Q K B . R\n";
+        let board = BoardBuilder::from_diagram(diagram)
+            .unwrap()
+            .side_to_move(PieceColors::Black)
+            .build()
+            .unwrap();
+        assert_eq!(board.to_string(), diagram);
+
+        let board = get_capablanca_starting_board();
+        let read_board = BoardBuilder::from_diagram(&board.to_string())
+            .unwrap()
+            .fairy_pieces(board.fairy_pieces.clone())
+            .build()
+            .unwrap();
+        assert_eq!(read_board.to_string(), board.to_string());
+    }
+
+    #[test]
+    fn fairy_pieces_must_be_given_to_the_builder() {
+        let diagram = "\
+            k . . . .
+            . . . . .
+            . . . . .
+            . . . . .
+            . A . . K";
+        let builder = BoardBuilder::from_diagram(diagram).unwrap();
+        assert_eq!(
+            builder.clone().build().unwrap_err(),
+            vec![BoardProblem::UnknownFairyPiece(position("b1"))]
+        );
+        assert!(builder
+            .fairy_pieces(vec![FairyPiece::ARCHBISHOP])
+            .build()
+            .is_ok());
+        assert!(matches!(
+            BoardBuilder::from_diagram(&diagram.replace('A', "*")),
+            Err(DiagramError::InvalidSymbol('*'))
+        ));
+    }
+
+    #[test]
+    fn pinned_pieces_only_move_along_the_pin() {
+        let board = BoardBuilder::from_diagram(
+            "\
+            . . . . r . . k
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . N . . .
+            . . . . K . . .",
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        assert_eq!(valid_positions(&board, "e2"), vec![]);
+
+        let board = BoardBuilder::from(&board)
+            .place(PieceTypes::Rook, PieceColors::White, position("e2"))
+            .build()
+            .unwrap();
+        let mut rook_positions = valid_positions(&board, "e2");
+        rook_positions.sort_by_key(|position| position.to_string());
+        assert_eq!(
+            rook_positions,
+            ["e3", "e4", "e5", "e6", "e7", "e8"].map(position)
+        );
+    }
+
+    #[test]
+    fn en_passant_cant_discover_a_check() {
+        let diagram = "\
+            . . . . . . . k
+            . . . . . . . .
+            . . . . . . . .
+            K P p . . . . r
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .";
+        let builder = BoardBuilder::from_diagram(diagram)
+            .unwrap()
+            .en_passant(Some(position("c5")));
+
+        let board = builder.clone().build().unwrap();
+        assert_eq!(valid_positions(&board, "b5"), vec![position("b6")]);
+
+        let board = builder.remove(&position("h5")).build().unwrap();
+        let pawn_positions = valid_positions(&board, "b5");
+        assert!(pawn_positions.contains(&position("c6")));
+    }
+}
@@ -0,0 +1,274 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// An inconsistency found on a board.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum BoardProblem {
+    #[error("The rows of the board have different lengths, or the board is too big or too small.")]
+    InvalidDimensions,
+    #[error("The piece on `{0}` has a different position than its cell.")]
+    PiecePositionMismatch(BoardPosition),
+    #[error("The piece on `{0}` is on the cells but not on the pieces of its color.")]
+    PieceMissingFromPieces(BoardPosition),
+    #[error("The piece on `{0}` is on the pieces of its color but not on the cells.")]
+    PieceMissingFromCells(BoardPosition),
+    #[error("The {0} king position `{1}` doesn't have a {0} king.")]
+    KingPositionMismatch(PieceColors, BoardPosition),
+    #[error("There's no {0} king on the board.")]
+    MissingKing(PieceColors),
+    #[error("There's more than one {0} king on the board.")]
+    DuplicateKings(PieceColors),
+    #[error("The piece on `{0}` is a fairy piece that's not on the fairy pieces of the board.")]
+    UnknownFairyPiece(BoardPosition),
+    #[error("There's more than one fairy piece written as `{0}`.")]
+    DuplicateFairyPiece(char),
+    #[error("There's a pawn on the back rank at `{0}`.")]
+    PawnOnBackRank(BoardPosition),
+    #[error("The {0} player can castle but the king is not on its starting position.")]
+    CastlingWithoutKing(PieceColors),
+    #[error("The {0} player can castle with a rook that's not on `{1}`.")]
+    CastlingWithoutRook(PieceColors, BoardPosition),
+    #[error("There can't be an en passant on `{0}`.")]
+    ImpossibleEnPassant(BoardPosition),
+    #[error("The {0} king is in check but it's not his turn.")]
+    SideNotToMoveInCheck(PieceColors),
+    #[error("The position `{0}` is outside of the board.")]
+    PositionOutsideBoard(BoardPosition),
+}
+
+impl Board {
+    /// Checks that all the representations of this board agree with each other and that the
+    /// position could happen on a game.
+    /// Returns every problem found, so an empty list means the board is valid.
+    pub fn validate(&self) -> Vec<BoardProblem> {
+        let dimensions = self.dimensions();
+        let valid_dimensions = dimensions.files <= MAX_FILES
+            && dimensions.ranks <= MAX_RANKS
+            && dimensions.ranks >= 4
+            && self.cells.iter().all(|row| row.len() == dimensions.files);
+        if !valid_dimensions {
+            return vec![BoardProblem::InvalidDimensions];
+        }
+
+        let mut problems = self.validate_cells();
+        for (index, piece) in self.fairy_pieces.iter().enumerate() {
+            if self.fairy_pieces[..index].contains(piece) {
+                problems.push(BoardProblem::DuplicateFairyPiece(piece.symbol()));
+            }
+        }
+        for color in [PieceColors::White, PieceColors::Black] {
+            problems.append(&mut self.validate_king(&color));
+            problems.append(&mut self.validate_castling(&color));
+        }
+
+        if let Some(position) = &self.en_passant_position {
+            if !self.is_en_passant_possible(position) {
+                problems.push(BoardProblem::ImpossibleEnPassant(position.clone()));
+            }
+        }
+
+        let waiting_color = self.side_to_move.opponent();
+        let waiting_king = self.get_king_position(&waiting_color);
+        let has_waiting_king = matches!(
+            self.get_piece(&waiting_king),
+            Some(piece) if piece.kind() == &PieceTypes::King && piece.color() == &waiting_color
+        );
+        if has_waiting_king && self.position_in_check(&waiting_king, &waiting_color) {
+            problems.push(BoardProblem::SideNotToMoveInCheck(waiting_color));
+        }
+
+        problems
+    }
+
+    fn validate_cells(&self) -> Vec<BoardProblem> {
+        let (white_back_rank, black_back_rank) = self.dimensions().back_ranks();
+        let mut problems = vec![];
+
+        for (row, cells_row) in self.cells.iter().enumerate() {
+            for (column, cell) in cells_row.iter().enumerate() {
+                let Some(piece) = cell.piece() else {
+                    continue;
+                };
+                let position: BoardPosition = match (row, column).try_into() {
+                    Ok(position) => position,
+                    Err(_) => continue,
+                };
+
+                if piece.board_position() != &position {
+                    problems.push(BoardProblem::PiecePositionMismatch(position.clone()));
+                }
+                if !self.get_pieces_from(piece.color()).contains(&piece) {
+                    problems.push(BoardProblem::PieceMissingFromPieces(position.clone()));
+                }
+                let is_unknown_fairy_piece = matches!(
+                    piece.kind(),
+                    PieceTypes::Fairy(_)
+                        if !self.fairy_pieces.iter().any(|fairy| &fairy.kind() == piece.kind())
+                );
+                if is_unknown_fairy_piece {
+                    problems.push(BoardProblem::UnknownFairyPiece(position.clone()));
+                }
+                if piece.kind() == &PieceTypes::Pawn
+                    && (row == white_back_rank || row == black_back_rank)
+                {
+                    problems.push(BoardProblem::PawnOnBackRank(position));
+                }
+            }
+        }
+
+        for piece in self.white_pieces.iter().chain(self.black_pieces.iter()) {
+            if self.get_piece(piece.board_position()).as_ref() != Some(piece) {
+                problems.push(BoardProblem::PieceMissingFromCells(
+                    piece.board_position().clone(),
+                ));
+            }
+        }
+
+        problems
+    }
+
+    fn validate_king(&self, color: &PieceColors) -> Vec<BoardProblem> {
+        let mut problems = vec![];
+        let kings = self
+            .cells
+            .iter()
+            .flatten()
+            .filter_map(ChessCell::piece)
+            .filter(|piece| piece.kind() == &PieceTypes::King && piece.color() == color)
+            .count();
+
+        match kings {
+            0 => problems.push(BoardProblem::MissingKing(color.clone())),
+            1 => {}
+            _ => problems.push(BoardProblem::DuplicateKings(color.clone())),
+        }
+
+        let king_position = self.get_king_position(color);
+        let is_king = matches!(
+            self.get_piece(&king_position),
+            Some(piece) if piece.kind() == &PieceTypes::King && piece.color() == color
+        );
+        if !is_king {
+            problems.push(BoardProblem::KingPositionMismatch(
+                color.clone(),
+                king_position,
+            ));
+        }
+
+        problems
+    }
+
+    fn validate_castling(&self, color: &PieceColors) -> Vec<BoardProblem> {
+        let Some(castling) = self.get_castling_state(color) else {
+            return vec![];
+        };
+
+        let dimensions = self.dimensions();
+        let (white_back_rank, black_back_rank) = dimensions.back_ranks();
+        let back_rank = match color {
+            PieceColors::White => white_back_rank,
+            PieceColors::Black => black_back_rank,
+        };
+
+        let mut problems = vec![];
+        let king = self.get_piece(&self.get_king_position(color));
+        let king_on_start = king.as_ref().map_or(false, |king| {
+            is_king_on_starting_position(king, &dimensions)
+        });
+        if !king_on_start {
+            problems.push(BoardProblem::CastlingWithoutKing(color.clone()));
+        }
+
+        let rooks = [
+            (castling.can_use_left_rook, 0),
+            (
+                castling.can_use_right_rook,
+                dimensions.files.saturating_sub(1),
+            ),
+        ];
+        for (can_use_rook, column) in rooks {
+            let Ok(position) = BoardPosition::try_from((back_rank, column)) else {
+                continue;
+            };
+            let has_rook = matches!(
+                self.get_piece(&position),
+                Some(piece) if piece.kind() == &PieceTypes::Rook && piece.color() == color
+            );
+            if can_use_rook && !has_rook {
+                problems.push(BoardProblem::CastlingWithoutRook(color.clone(), position));
+            }
+        }
+
+        problems
+    }
+
+    /// Checks if the pawn on the given position could have just moved two cells forward.
+    fn is_en_passant_possible(&self, position: &BoardPosition) -> bool {
+        let dimensions = self.dimensions();
+        let (white_back_rank, black_back_rank) = dimensions.back_ranks();
+        let pawn_color = self.side_to_move.opponent();
+        let (row, column) = position.into();
+
+        let (expected_row, passed_row, origin_row) = match pawn_color {
+            PieceColors::White => (
+                white_back_rank + 3,
+                white_back_rank + 2,
+                white_back_rank + 1,
+            ),
+            PieceColors::Black => (
+                black_back_rank - 3,
+                black_back_rank - 2,
+                black_back_rank - 1,
+            ),
+        };
+
+        let is_pawn = matches!(
+            self.get_piece(position),
+            Some(piece) if piece.kind() == &PieceTypes::Pawn && piece.color() == &pawn_color
+        );
+        let is_empty = |row: usize| {
+            BoardPosition::try_from((row, column))
+                .map_or(false, |position| self.get_piece(&position).is_none())
+        };
+
+        dimensions.allows_pawn_double_step()
+            && is_pawn
+            && row == expected_row
+            && is_empty(passed_row)
+            && is_empty(origin_row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fairy_pieces_must_be_on_the_board_fairy_pieces() {
+        let mut board = get_capablanca_starting_board();
+        assert_eq!(board.validate(), vec![]);
+
+        let position: BoardPosition = "e4".try_into().unwrap();
+        for kind in [PieceTypes::AMAZON, PieceTypes::Fairy('Q')] {
+            let piece = ChessPiece::new(kind, position.clone(), PieceColors::White);
+            let (row, column) = (&position).into();
+            board.cells[row][column] = ChessCell::some(piece.clone());
+            board
+                .white_pieces
+                .retain(|piece| piece.board_position() != &position);
+            board.white_pieces.push(piece);
+
+            assert_eq!(
+                board.validate(),
+                vec![BoardProblem::UnknownFairyPiece(position.clone())]
+            );
+        }
+
+        board.fairy_pieces.push(FairyPiece::ARCHBISHOP);
+        assert!(board
+            .validate()
+            .contains(&BoardProblem::DuplicateFairyPiece('A')));
+    }
+}
@@ -0,0 +1,486 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The amount of halfmoves without captures nor pawn movements after which a player can claim a
+/// draw.
+const FIFTY_MOVE_RULE_HALFMOVES: usize = 100;
+/// The amount of halfmoves without captures nor pawn movements after which the game is drawn.
+const SEVENTY_FIVE_MOVE_RULE_HALFMOVES: usize = 150;
+
+/// The rules a game is played with.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameVariant {
+    #[default]
+    Standard,
+    /// Each player only sees the cells his pieces can move to. The game is won by capturing the
+    /// opponent king.
+    FogOfWar,
+}
+
+impl GameVariant {
+    /// Moves a piece on the board following the rules of this variant.
+    pub fn move_piece(
+        &self,
+        movement: BoardMovement,
+        board: &mut Board,
+    ) -> Result<MoveRecord, MovementError> {
+        match self {
+            GameVariant::Standard => move_piece(movement, board),
+            GameVariant::FogOfWar => move_piece_fog_of_war(movement, board),
+        }
+    }
+}
+
+/// Why a game ended in a draw.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    /// The same position happened three times and a player claimed the draw.
+    ThreefoldRepetition,
+    /// The same position happened five times.
+    FivefoldRepetition,
+    /// Fifty moves were played without captures nor pawn movements and a player claimed the draw.
+    FiftyMoveRule,
+    /// Seventy five moves were played without captures nor pawn movements.
+    SeventyFiveMoveRule,
+    /// Neither player can checkmate anymore.
+    InsufficientMaterial,
+    /// A player ran out of time but his opponent can't checkmate.
+    TimeoutVsInsufficientMaterial,
+    Agreement,
+}
+
+/// The state of a game.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate { winner: PieceColors },
+    KingCaptured { winner: PieceColors },
+    Stalemate,
+    Draw(DrawReason),
+    Resignation { winner: PieceColors },
+    Timeout { winner: PieceColors },
+}
+
+/// The final result of a game.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl std::fmt::Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameResult::WhiteWins => write!(f, "1-0"),
+            GameResult::BlackWins => write!(f, "0-1"),
+            GameResult::Draw => write!(f, "1/2-1/2"),
+        }
+    }
+}
+
+impl GameResult {
+    /// Get's the result of a game won by the player of the given color.
+    pub fn win_for(winner: &PieceColors) -> Self {
+        match winner {
+            PieceColors::Black => GameResult::BlackWins,
+            PieceColors::White => GameResult::WhiteWins,
+        }
+    }
+}
+
+impl GameStatus {
+    /// Checks if the game ended.
+    pub fn is_over(&self) -> bool {
+        self != &GameStatus::Ongoing
+    }
+
+    /// Get's the result of the game, if it ended.
+    pub fn result(&self) -> Option<GameResult> {
+        match self {
+            GameStatus::Ongoing => None,
+            GameStatus::Checkmate { winner }
+            | GameStatus::KingCaptured { winner }
+            | GameStatus::Resignation { winner }
+            | GameStatus::Timeout { winner } => Some(GameResult::win_for(winner)),
+            GameStatus::Stalemate | GameStatus::Draw(_) => Some(GameResult::Draw),
+        }
+    }
+}
+
+impl Board {
+    /// Get's how a game on this board stands, only looking at this position.
+    pub fn status(&self) -> GameStatus {
+        let color = &self.side_to_move;
+        if self.is_checkmate(color) {
+            GameStatus::Checkmate {
+                winner: color.opponent(),
+            }
+        } else if self.is_stalemate(color) {
+            GameStatus::Stalemate
+        } else if self.is_insufficient_material() {
+            GameStatus::Draw(DrawReason::InsufficientMaterial)
+        } else {
+            GameStatus::Ongoing
+        }
+    }
+}
+
+#[derive(Debug, Error, Serialize)]
+pub enum GameError {
+    #[error("The game is already over.")]
+    GameOver,
+    #[error("There's no draw to claim.")]
+    NoDrawToClaim,
+    #[error(transparent)]
+    Movement(#[from] MovementError),
+    #[error(transparent)]
+    UciMove(#[from] UciMoveError),
+}
+
+/// A game of chess, with every movement played on it and whether it ended.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChessGame {
+    variant: GameVariant,
+    initial_board: Board,
+    board: Board,
+    history: Vec<MoveRecord>,
+    /// The amount of halfmoves since the last capture or pawn movement.
+    halfmove_clock: usize,
+    /// The number of the current move, that starts at 1 and increases after black moves.
+    fullmove_number: usize,
+    status: GameStatus,
+}
+
+impl Default for ChessGame {
+    fn default() -> Self {
+        ChessGame::new(get_starting_board(), GameVariant::Standard)
+    }
+}
+
+impl ChessGame {
+    /// Creates a game that starts on the given board.
+    pub fn new(board: Board, variant: GameVariant) -> Self {
+        ChessGame::with_clocks(board, variant, 0, 1)
+    }
+
+    /// Creates a game that starts on the given board, with the halfmove clock and move number of
+    /// a game that was already going on.
+    pub fn with_clocks(
+        board: Board,
+        variant: GameVariant,
+        halfmove_clock: usize,
+        fullmove_number: usize,
+    ) -> Self {
+        ChessGame {
+            variant,
+            initial_board: board.clone(),
+            board,
+            history: vec![],
+            halfmove_clock,
+            fullmove_number,
+            status: GameStatus::Ongoing,
+        }
+    }
+
+    pub fn variant(&self) -> GameVariant {
+        self.variant
+    }
+
+    pub fn initial_board(&self) -> &Board {
+        &self.initial_board
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn history(&self) -> &[MoveRecord] {
+        &self.history
+    }
+
+    pub fn halfmove_clock(&self) -> usize {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> usize {
+        self.fullmove_number
+    }
+
+    pub fn status(&self) -> &GameStatus {
+        &self.status
+    }
+
+    /// Get's the result of the game, if it ended.
+    pub fn result(&self) -> Option<GameResult> {
+        self.status.result()
+    }
+
+    /// Plays the movement, following the rules of the game variant.
+    /// The game ends by itself on checkmate, stalemate and the draws that don't need to be
+    /// claimed.
+    pub fn make_move(&mut self, movement: BoardMovement) -> Result<MoveRecord, GameError> {
+        if self.status.is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        let record = self.variant.move_piece(movement, &mut self.board)?;
+
+        if record.piece.kind() == &PieceTypes::Pawn || record.captured_piece.is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if record.piece.color() == &PieceColors::Black {
+            self.fullmove_number += 1;
+        }
+        self.history.push(record.clone());
+
+        let winner = record.piece.color().clone();
+        self.status = match record.outcome {
+            MovementSuccess::CheckmateMovement => GameStatus::Checkmate { winner },
+            MovementSuccess::KingCaptureMovement => GameStatus::KingCaptured { winner },
+            MovementSuccess::StalemateMovement => GameStatus::Stalemate,
+            MovementSuccess::NormalMovement => self
+                .get_automatic_draw()
+                .map_or(GameStatus::Ongoing, GameStatus::Draw),
+        };
+
+        Ok(record)
+    }
+
+    /// Get's how many times the current position happened since the last capture or pawn
+    /// movement, counting the current one.
+    pub fn repetition_count(&self) -> usize {
+        let hashes: Vec<u64> = std::iter::once(self.initial_board.zobrist_hash())
+            .chain(self.history.iter().map(|record| record.hash))
+            .collect();
+        let reversible_positions = hashes.len().min(self.halfmove_clock + 1);
+        let current_hash = self.board.zobrist_hash();
+
+        hashes
+            .iter()
+            .rev()
+            .take(reversible_positions)
+            .filter(|hash| **hash == current_hash)
+            .count()
+    }
+
+    /// Get's the draw a player could claim right now, if there's one.
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.status.is_over() {
+            None
+        } else if self.repetition_count() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.halfmove_clock >= FIFTY_MOVE_RULE_HALFMOVES {
+            Some(DrawReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the game in a draw by threefold repetition or by the fifty move rule.
+    pub fn claim_draw(&mut self) -> Result<DrawReason, GameError> {
+        if self.status.is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        let reason = self.claimable_draw().ok_or(GameError::NoDrawToClaim)?;
+        self.status = GameStatus::Draw(reason);
+        Ok(reason)
+    }
+
+    /// Ends the game in a draw both players agreed on.
+    pub fn agree_draw(&mut self) -> Result<(), GameError> {
+        self.end(GameStatus::Draw(DrawReason::Agreement))
+    }
+
+    /// Ends the game because the player of the given color resigned.
+    pub fn resign(&mut self, color: &PieceColors) -> Result<(), GameError> {
+        self.end(GameStatus::Resignation {
+            winner: color.opponent(),
+        })
+    }
+
+    /// Ends the game because the player of the given color ran out of time.
+    /// It's a draw if the opponent can't checkmate.
+    pub fn timeout(&mut self, color: &PieceColors) -> Result<(), GameError> {
+        let winner = color.opponent();
+        if self.board.has_insufficient_material(&winner) {
+            self.end(GameStatus::Draw(DrawReason::TimeoutVsInsufficientMaterial))
+        } else {
+            self.end(GameStatus::Timeout { winner })
+        }
+    }
+
+    fn end(&mut self, status: GameStatus) -> Result<(), GameError> {
+        if self.status.is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        self.status = status;
+        Ok(())
+    }
+
+    /// Get's the draw the game ends with without anyone claiming it, if there's one.
+    fn get_automatic_draw(&self) -> Option<DrawReason> {
+        if self.repetition_count() >= 5 {
+            Some(DrawReason::FivefoldRepetition)
+        } else if self.halfmove_clock >= SEVENTY_FIVE_MOVE_RULE_HALFMOVES {
+            Some(DrawReason::SeventyFiveMoveRule)
+        } else if self.board.is_insufficient_material() {
+            Some(DrawReason::InsufficientMaterial)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut ChessGame, from: &str, to: &str) -> Result<MoveRecord, GameError> {
+        let piece = game.board().get_piece(&from.try_into().unwrap()).unwrap();
+        game.make_move(BoardMovement {
+            piece,
+            destination: to.try_into().unwrap(),
+            promotion: None,
+        })
+    }
+
+    /// Moves the knights out and back, repeating the starting position once more.
+    fn shuffle_knights(game: &mut ChessGame) {
+        for (from, to) in [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")] {
+            play(game, from, to).unwrap();
+        }
+    }
+
+    #[test]
+    fn threefold_repetition_can_be_claimed() {
+        let mut game = ChessGame::default();
+        assert_eq!(game.repetition_count(), 1);
+
+        shuffle_knights(&mut game);
+        assert_eq!(game.repetition_count(), 2);
+        assert_eq!(game.claimable_draw(), None);
+        assert!(matches!(game.claim_draw(), Err(GameError::NoDrawToClaim)));
+
+        shuffle_knights(&mut game);
+        assert_eq!(game.repetition_count(), 3);
+        assert_eq!(game.claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+        assert_eq!(game.claim_draw().unwrap(), DrawReason::ThreefoldRepetition);
+        assert_eq!(
+            game.status(),
+            &GameStatus::Draw(DrawReason::ThreefoldRepetition)
+        );
+        assert_eq!(game.result(), Some(GameResult::Draw));
+        assert_eq!(game.claimable_draw(), None);
+    }
+
+    #[test]
+    fn repetitions_only_count_since_the_last_pawn_movement() {
+        let mut game = ChessGame::default();
+        shuffle_knights(&mut game);
+        play(&mut game, "e2", "e4").unwrap();
+        play(&mut game, "e7", "e5").unwrap();
+        assert_eq!(game.repetition_count(), 1);
+
+        shuffle_knights(&mut game);
+        assert_eq!(game.repetition_count(), 2);
+    }
+
+    #[test]
+    fn fifty_move_rule_can_be_claimed() {
+        let mut game = ChessGame::with_clocks(get_starting_board(), GameVariant::Standard, 98, 50);
+        play(&mut game, "g1", "f3").unwrap();
+        assert_eq!(game.halfmove_clock(), 99);
+        assert_eq!(game.claimable_draw(), None);
+
+        play(&mut game, "g8", "f6").unwrap();
+        assert_eq!(game.halfmove_clock(), 100);
+        assert_eq!(game.fullmove_number(), 51);
+        assert_eq!(game.claimable_draw(), Some(DrawReason::FiftyMoveRule));
+        assert_eq!(game.claim_draw().unwrap(), DrawReason::FiftyMoveRule);
+        assert_eq!(game.status(), &GameStatus::Draw(DrawReason::FiftyMoveRule));
+    }
+
+    #[test]
+    fn games_can_be_agreed_drawn_or_resigned() {
+        let mut game = ChessGame::default();
+        game.agree_draw().unwrap();
+        assert_eq!(game.status(), &GameStatus::Draw(DrawReason::Agreement));
+        assert_eq!(game.result(), Some(GameResult::Draw));
+
+        let mut game = ChessGame::default();
+        game.resign(&PieceColors::White).unwrap();
+        assert_eq!(
+            game.status(),
+            &GameStatus::Resignation {
+                winner: PieceColors::Black
+            }
+        );
+        assert_eq!(game.result(), Some(GameResult::BlackWins));
+    }
+
+    #[test]
+    fn timeouts_are_draws_if_the_opponent_cant_checkmate() {
+        let board = BoardBuilder::from_diagram(
+            "\
+            . . . . k . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            R . . . K . . .",
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let mut game = ChessGame::new(board.clone(), GameVariant::Standard);
+        game.timeout(&PieceColors::White).unwrap();
+        assert_eq!(
+            game.status(),
+            &GameStatus::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+        );
+
+        let mut game = ChessGame::new(board, GameVariant::Standard);
+        game.timeout(&PieceColors::Black).unwrap();
+        assert_eq!(
+            game.status(),
+            &GameStatus::Timeout {
+                winner: PieceColors::White
+            }
+        );
+        assert_eq!(game.result(), Some(GameResult::WhiteWins));
+    }
+
+    #[test]
+    fn finished_games_cant_change() {
+        let mut game = ChessGame::default();
+        game.resign(&PieceColors::Black).unwrap();
+        let status = game.status().clone();
+
+        assert!(matches!(
+            game.resign(&PieceColors::White),
+            Err(GameError::GameOver)
+        ));
+        assert!(matches!(
+            game.timeout(&PieceColors::White),
+            Err(GameError::GameOver)
+        ));
+        assert!(matches!(game.agree_draw(), Err(GameError::GameOver)));
+        assert!(matches!(game.claim_draw(), Err(GameError::GameOver)));
+        assert!(matches!(
+            play(&mut game, "e2", "e4"),
+            Err(GameError::GameOver)
+        ));
+        assert_eq!(game.status(), &status);
+        assert!(game.history().is_empty());
+    }
+}
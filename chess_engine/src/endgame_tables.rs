@@ -0,0 +1,842 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// The bytes every table file starts with.
+const TABLE_MAGIC: &[u8; 8] = b"CHESSYTB";
+const TABLE_EXTENSION: &str = "tb";
+/// The most pieces, kings included, a table can have.
+const MAX_TABLE_PIECES: usize = 4;
+/// The value of the positions that can't happen on a game.
+const ILLEGAL: u8 = u8::MAX;
+/// The value of the positions that are not won or lost. Any other value is the distance to mate
+/// in plies plus one.
+const DRAW: u8 = 0;
+/// The count of movements of a position where the lone king can capture a piece, so it can't be
+/// lost.
+const CAN_ESCAPE: u8 = u8::MAX;
+
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+    (1, -2),
+    (2, -1),
+];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+#[derive(Debug, Error)]
+pub enum EndgameTableError {
+    #[error("The file is not an endgame table.")]
+    InvalidHeader,
+    #[error("The table has {0} positions, but its material needs {1}.")]
+    InvalidLength(usize, usize),
+    #[error("The table file can't be read or written: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The pieces of an endgame where one side only has its king.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndgameMaterial {
+    KingQueen,
+    KingRook,
+    KingBishopKnight,
+    KingPawn,
+}
+
+impl std::fmt::Display for EndgameMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            EndgameMaterial::KingQueen => "KQK",
+            EndgameMaterial::KingRook => "KRK",
+            EndgameMaterial::KingBishopKnight => "KBNK",
+            EndgameMaterial::KingPawn => "KPK",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl EndgameMaterial {
+    pub const ALL: [EndgameMaterial; 4] = [
+        EndgameMaterial::KingQueen,
+        EndgameMaterial::KingRook,
+        EndgameMaterial::KingBishopKnight,
+        EndgameMaterial::KingPawn,
+    ];
+
+    /// Get's the pieces the strong side has besides its king.
+    pub fn pieces(&self) -> &'static [PieceTypes] {
+        match self {
+            EndgameMaterial::KingQueen => &[PieceTypes::Queen],
+            EndgameMaterial::KingRook => &[PieceTypes::Rook],
+            EndgameMaterial::KingBishopKnight => &[PieceTypes::Bishop, PieceTypes::Knight],
+            EndgameMaterial::KingPawn => &[PieceTypes::Pawn],
+        }
+    }
+
+    fn id(&self) -> u8 {
+        match self {
+            EndgameMaterial::KingQueen => 0,
+            EndgameMaterial::KingRook => 1,
+            EndgameMaterial::KingBishopKnight => 2,
+            EndgameMaterial::KingPawn => 3,
+        }
+    }
+
+    fn pieces_count(&self) -> usize {
+        self.pieces().len() + 2
+    }
+
+    /// Get's how many positions the table of this material has.
+    fn table_len(&self) -> usize {
+        2 * 64usize.pow(self.pieces_count() as u32)
+    }
+}
+
+/// The distance to mate of every position of an endgame, built by retrograde analysis.
+#[derive(Debug, Clone)]
+pub struct EndgameTable {
+    material: EndgameMaterial,
+    values: Vec<u8>,
+}
+
+impl EndgameTable {
+    /// Builds the table of the material, working backwards from every checkmate.
+    /// The table of pawn endgames needs the ones of the pieces the pawn promotes to, so they are
+    /// built too. Building the table of the bishop and knight takes a while, as it has millions
+    /// of positions.
+    pub fn generate(material: EndgameMaterial) -> Self {
+        EndgameTables::default().generate_table(material)
+    }
+
+    /// Reads a table from the bytes of a table file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EndgameTableError> {
+        let (header, values) = bytes
+            .split_at_checked(TABLE_MAGIC.len() + 1)
+            .ok_or(EndgameTableError::InvalidHeader)?;
+        if &header[..TABLE_MAGIC.len()] != TABLE_MAGIC {
+            return Err(EndgameTableError::InvalidHeader);
+        }
+        let material = EndgameMaterial::ALL
+            .into_iter()
+            .find(|material| material.id() == header[TABLE_MAGIC.len()])
+            .ok_or(EndgameTableError::InvalidHeader)?;
+        if values.len() != material.table_len() {
+            return Err(EndgameTableError::InvalidLength(
+                values.len(),
+                material.table_len(),
+            ));
+        }
+
+        Ok(EndgameTable {
+            material,
+            values: values.to_vec(),
+        })
+    }
+
+    /// Reads a table file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EndgameTableError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Get's the table as the bytes of a table file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&TABLE_MAGIC[..], &[self.material.id()], &self.values].concat()
+    }
+
+    /// Writes the table to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EndgameTableError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn material(&self) -> EndgameMaterial {
+        self.material
+    }
+
+    /// Get's the distance to mate of the position, or `None` if the board doesn't have the
+    /// material of the table.
+    pub fn probe_dtm(&self, board: &Board) -> Option<DistanceToMate> {
+        let position = TablePosition::from_board(board, self.material)?;
+        let value = self.values[position.index(self.material.pieces_count())];
+
+        match value {
+            ILLEGAL => None,
+            DRAW => Some(DistanceToMate::Draw),
+            value if position.white_to_move => Some(DistanceToMate::Win(value as usize - 1)),
+            value => Some(DistanceToMate::Loss(value as usize - 1)),
+        }
+    }
+}
+
+/// A set of endgame tables, that can be used by the search as a tablebase.
+#[derive(Debug, Clone, Default)]
+pub struct EndgameTables {
+    tables: Vec<EndgameTable>,
+}
+
+impl EndgameTables {
+    /// Builds the tables of every material.
+    pub fn generate() -> Self {
+        let mut tables = EndgameTables::default();
+        for material in EndgameMaterial::ALL {
+            let table = tables.generate_table(material);
+            tables.add(table);
+        }
+        tables
+    }
+
+    /// Builds the table of the material, reusing the tables of the promoted pieces if they are
+    /// already built.
+    fn generate_table(&self, material: EndgameMaterial) -> EndgameTable {
+        let promotions: Vec<EndgameTable> = match material {
+            EndgameMaterial::KingPawn => [EndgameMaterial::KingQueen, EndgameMaterial::KingRook]
+                .into_iter()
+                .map(|promoted| {
+                    self.get(promoted)
+                        .cloned()
+                        .unwrap_or_else(|| EndgameTable::generate(promoted))
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        TableGenerator::new(material).generate(&promotions)
+    }
+
+    /// Adds a table, replacing the one of the same material.
+    pub fn add(&mut self, table: EndgameTable) {
+        self.tables
+            .retain(|current| current.material() != table.material());
+        self.tables.push(table);
+    }
+
+    pub fn get(&self, material: EndgameMaterial) -> Option<&EndgameTable> {
+        self.tables
+            .iter()
+            .find(|table| table.material() == material)
+    }
+
+    /// Reads the tables saved on a directory. Materials without a file are skipped.
+    pub fn open_directory(path: impl AsRef<Path>) -> Result<Self, EndgameTableError> {
+        let mut tables = EndgameTables::default();
+        for material in EndgameMaterial::ALL {
+            let file = path
+                .as_ref()
+                .join(format!("{}.{}", material, TABLE_EXTENSION));
+            if file.exists() {
+                tables.add(EndgameTable::open(file)?);
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Writes every table to a directory, with a file named after its material, like `KQK.tb`.
+    pub fn save_directory(&self, path: impl AsRef<Path>) -> Result<(), EndgameTableError> {
+        std::fs::create_dir_all(&path)?;
+        for table in &self.tables {
+            let file = path
+                .as_ref()
+                .join(format!("{}.{}", table.material(), TABLE_EXTENSION));
+            table.save(file)?;
+        }
+        Ok(())
+    }
+
+    /// Get's the table that has the material of the board and the distance to mate it knows
+    /// for it, or `None` if there's no table for it.
+    fn probe_table(&self, board: &Board) -> Option<(&EndgameTable, DistanceToMate)> {
+        self.tables
+            .iter()
+            .find_map(|table| Some((table, table.probe_dtm(board)?)))
+    }
+}
+
+/// The tables know the distance to mate. Without pawns the only movements that zero the fifty
+/// move counter are the captures of the lone king, which draw, so the distance to mate is also
+/// the distance to zero. None of the endgames they solve is long enough for the fifty move rule
+/// to matter.
+impl EndgameTablebase for EndgameTables {
+    fn max_pieces(&self) -> usize {
+        self.tables
+            .iter()
+            .map(|table| table.material().pieces_count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn probe_wdl(&self, board: &Board) -> Option<Wdl> {
+        Some(match self.probe_table(board)?.1 {
+            DistanceToMate::Win(_) => Wdl::Win,
+            DistanceToMate::Loss(_) => Wdl::Loss,
+            DistanceToMate::Draw => Wdl::Draw,
+        })
+    }
+
+    /// Pawn movements zero the fifty move counter, so the tables don't know the distance to zero
+    /// of pawn endgames.
+    fn probe_dtz(&self, board: &Board) -> Option<i32> {
+        let (table, dtm) = self.probe_table(board)?;
+        if table.material().pieces().contains(&PieceTypes::Pawn) {
+            return None;
+        }
+
+        Some(match dtm {
+            DistanceToMate::Win(plies) => plies as i32,
+            DistanceToMate::Loss(plies) => -(plies.max(1) as i32),
+            DistanceToMate::Draw => 0,
+        })
+    }
+
+    fn probe_dtm(&self, board: &Board) -> Option<DistanceToMate> {
+        Some(self.probe_table(board)?.1)
+    }
+}
+
+/// A position of a table, where white is the side with the pieces.
+/// The squares go from 0 for a1 to 63 for h8, and are ordered as the white king, the black king
+/// and then the pieces of the material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TablePosition {
+    squares: [u8; MAX_TABLE_PIECES],
+    white_to_move: bool,
+}
+
+impl TablePosition {
+    fn from_index(index: usize, pieces_count: usize) -> Self {
+        let mut squares = [0; MAX_TABLE_PIECES];
+        let mut rest = index / 2;
+        for square in squares[..pieces_count].iter_mut().rev() {
+            *square = (rest % 64) as u8;
+            rest /= 64;
+        }
+
+        TablePosition {
+            squares,
+            white_to_move: index % 2 == 0,
+        }
+    }
+
+    fn index(&self, pieces_count: usize) -> usize {
+        let squares = self.squares[..pieces_count]
+            .iter()
+            .fold(0, |index, square| index * 64 + *square as usize);
+        squares * 2 + usize::from(!self.white_to_move)
+    }
+
+    /// Get's the position of the board, turning it around if black has the pieces.
+    /// Returns `None` if the board doesn't have the material.
+    fn from_board(board: &Board, material: EndgameMaterial) -> Option<Self> {
+        if board.dimensions() != BoardDimensions::STANDARD {
+            return None;
+        }
+        let (strong_color, strong_pieces, weak_pieces) = match board.black_pieces.len() {
+            1 => (PieceColors::White, &board.white_pieces, &board.black_pieces),
+            _ => (PieceColors::Black, &board.black_pieces, &board.white_pieces),
+        };
+        if weak_pieces.len() != 1 || strong_pieces.len() != material.pieces_count() - 1 {
+            return None;
+        }
+
+        let square = |piece: &ChessPiece| -> u8 {
+            let (row, column) = piece.position();
+            let row = match strong_color {
+                PieceColors::White => row,
+                PieceColors::Black => 7 - row,
+            };
+            (row * 8 + column) as u8
+        };
+        let find_square = |kind: &PieceTypes| -> Option<u8> {
+            strong_pieces
+                .iter()
+                .find(|piece| piece.kind() == kind)
+                .map(square)
+        };
+        if weak_pieces[0].kind() != &PieceTypes::King {
+            return None;
+        }
+
+        let mut squares = [0; MAX_TABLE_PIECES];
+        squares[0] = find_square(&PieceTypes::King)?;
+        squares[1] = square(&weak_pieces[0]);
+        for (index, kind) in material.pieces().iter().enumerate() {
+            squares[index + 2] = find_square(kind)?;
+        }
+
+        Some(TablePosition {
+            squares,
+            white_to_move: board.side_to_move == strong_color,
+        })
+    }
+
+    fn white_king(&self) -> u8 {
+        self.squares[0]
+    }
+
+    fn black_king(&self) -> u8 {
+        self.squares[1]
+    }
+
+    fn is_occupied(&self, square: u8, pieces_count: usize) -> bool {
+        self.squares[..pieces_count].contains(&square)
+    }
+}
+
+/// Builds a table by retrograde analysis: it starts from the checkmates and goes back one ply at
+/// a time to the positions that lead to them.
+struct TableGenerator {
+    material: EndgameMaterial,
+    pieces_count: usize,
+    values: Vec<u8>,
+    /// How many movements of each position with black to move aren't known to lose yet.
+    counts: Vec<u8>,
+}
+
+impl TableGenerator {
+    fn new(material: EndgameMaterial) -> Self {
+        TableGenerator {
+            material,
+            pieces_count: material.pieces_count(),
+            values: vec![DRAW; material.table_len()],
+            counts: vec![0; material.table_len()],
+        }
+    }
+
+    fn generate(mut self, promotions: &[EndgameTable]) -> EndgameTable {
+        let mut decided = vec![];
+        // The positions that are won by promoting the pawn, by the plies it takes to mate.
+        let mut promotion_wins: Vec<Vec<usize>> = vec![];
+
+        for index in 0..self.values.len() {
+            let position = TablePosition::from_index(index, self.pieces_count);
+            if !self.is_legal(&position) {
+                self.values[index] = ILLEGAL;
+                continue;
+            }
+
+            if position.white_to_move {
+                if let Some(plies) = self.get_promotion_win(&position, promotions) {
+                    if promotion_wins.len() <= plies {
+                        promotion_wins.resize(plies + 1, vec![]);
+                    }
+                    promotion_wins[plies].push(index);
+                }
+                continue;
+            }
+
+            let movements = self.get_black_movements(&position);
+            self.counts[index] = movements.unwrap_or(CAN_ESCAPE);
+            if movements == Some(0) && self.is_black_in_check(&position) {
+                self.values[index] = 1;
+                decided.push(index);
+            }
+        }
+
+        let mut plies = 0;
+        while !decided.is_empty() || plies < promotion_wins.len() {
+            if let Some(wins) = promotion_wins.get(plies) {
+                for index in wins {
+                    if self.values[*index] == DRAW {
+                        self.values[*index] = plies as u8 + 1;
+                        decided.push(*index);
+                    }
+                }
+            }
+
+            let mut next_decided = vec![];
+            for index in decided {
+                let position = TablePosition::from_index(index, self.pieces_count);
+                for previous in self.get_previous_positions(&position) {
+                    let previous_index = previous.index(self.pieces_count);
+                    if self.values[previous_index] != DRAW {
+                        continue;
+                    }
+
+                    let is_decided = if previous.white_to_move {
+                        true
+                    } else {
+                        let count = &mut self.counts[previous_index];
+                        if *count == CAN_ESCAPE || *count == 0 {
+                            continue;
+                        }
+                        *count -= 1;
+                        *count == 0
+                    };
+                    if is_decided {
+                        self.values[previous_index] = plies as u8 + 2;
+                        next_decided.push(previous_index);
+                    }
+                }
+            }
+
+            decided = next_decided;
+            plies += 1;
+        }
+
+        EndgameTable {
+            material: self.material,
+            values: self.values,
+        }
+    }
+
+    fn kind(&self, piece: usize) -> &'static PieceTypes {
+        &self.material.pieces()[piece - 2]
+    }
+
+    /// Checks if the position can happen on a game: no two pieces share a square, the kings are
+    /// not next to each other, the pawns are not on the first or last rank and the player that
+    /// doesn't move is not in check.
+    fn is_legal(&self, position: &TablePosition) -> bool {
+        let squares = &position.squares[..self.pieces_count];
+        let has_repeated_squares = squares
+            .iter()
+            .enumerate()
+            .any(|(index, square)| squares[..index].contains(square));
+        let has_pawn_on_last_ranks = (2..self.pieces_count).any(|piece| {
+            self.kind(piece) == &PieceTypes::Pawn && !(8..56).contains(&squares[piece])
+        });
+
+        !has_repeated_squares
+            && !has_pawn_on_last_ranks
+            && !are_adjacent(position.white_king(), position.black_king())
+            && (!position.white_to_move || !self.is_black_in_check(position))
+    }
+
+    fn is_black_in_check(&self, position: &TablePosition) -> bool {
+        self.is_attacked_by_white(position, position.black_king(), None)
+    }
+
+    /// Checks if a white piece attacks the square, leaving out the piece with the given index.
+    /// The black king doesn't block the attacks, as it's the piece that's trying to move away.
+    fn is_attacked_by_white(
+        &self,
+        position: &TablePosition,
+        target: u8,
+        skipped_piece: Option<usize>,
+    ) -> bool {
+        if are_adjacent(position.white_king(), target) {
+            return true;
+        }
+
+        let is_blocking = |square: u8| -> bool {
+            (0..self.pieces_count).any(|piece| piece != 1 && position.squares[piece] == square)
+        };
+        let attacks_through = |origin: u8, directions: &[(i8, i8)]| -> bool {
+            directions.iter().any(|direction| {
+                let mut square = origin;
+                while let Some(next) = offset(square, *direction) {
+                    if next == target {
+                        return true;
+                    }
+                    if is_blocking(next) {
+                        return false;
+                    }
+                    square = next;
+                }
+                false
+            })
+        };
+
+        (2..self.pieces_count)
+            .filter(|piece| Some(*piece) != skipped_piece)
+            .any(|piece| {
+                let origin = position.squares[piece];
+                match self.kind(piece) {
+                    PieceTypes::Queen => {
+                        attacks_through(origin, &ROOK_DIRECTIONS)
+                            || attacks_through(origin, &BISHOP_DIRECTIONS)
+                    }
+                    PieceTypes::Rook => attacks_through(origin, &ROOK_DIRECTIONS),
+                    PieceTypes::Bishop => attacks_through(origin, &BISHOP_DIRECTIONS),
+                    PieceTypes::Knight => KNIGHT_OFFSETS
+                        .iter()
+                        .any(|knight_offset| offset(origin, *knight_offset) == Some(target)),
+                    PieceTypes::Pawn => [(1, 1), (1, -1)]
+                        .iter()
+                        .any(|pawn_offset| offset(origin, *pawn_offset) == Some(target)),
+                    _ => false,
+                }
+            })
+    }
+
+    /// Get's how many legal movements the black king has, or `None` if it can capture a piece
+    /// and reach a draw.
+    fn get_black_movements(&self, position: &TablePosition) -> Option<u8> {
+        let mut movements = 0;
+        for king_offset in KING_OFFSETS {
+            let Some(destination) = offset(position.black_king(), king_offset) else {
+                continue;
+            };
+            if are_adjacent(destination, position.white_king()) {
+                continue;
+            }
+
+            let captured_piece =
+                (2..self.pieces_count).find(|piece| position.squares[*piece] == destination);
+            if self.is_attacked_by_white(position, destination, captured_piece) {
+                continue;
+            }
+            if captured_piece.is_some() {
+                return None;
+            }
+            movements += 1;
+        }
+
+        Some(movements)
+    }
+
+    /// Get's the fewest plies it takes to mate by promoting the pawn, if it wins. Promoting to a
+    /// bishop or a knight never wins, so only the tables of the other pieces are given.
+    fn get_promotion_win(
+        &self,
+        position: &TablePosition,
+        promotions: &[EndgameTable],
+    ) -> Option<usize> {
+        promotions
+            .iter()
+            .flat_map(|table| {
+                (2..self.pieces_count)
+                    .filter(|piece| self.kind(*piece) == &PieceTypes::Pawn)
+                    .filter_map(move |pawn| {
+                        let destination = offset(position.squares[pawn], (1, 0))?;
+                        if destination < 56 || position.is_occupied(destination, self.pieces_count)
+                        {
+                            return None;
+                        }
+
+                        let promoted = TablePosition {
+                            squares: [position.white_king(), position.black_king(), destination, 0],
+                            white_to_move: false,
+                        };
+                        // The promoted position loses in one ply less than the value, so the
+                        // promotion wins in as many plies as the value.
+                        match table.values[promoted.index(table.material().pieces_count())] {
+                            ILLEGAL | DRAW => None,
+                            value => Some(value as usize),
+                        }
+                    })
+            })
+            .min()
+    }
+
+    /// Get's the positions that lead to this one with a single movement that doesn't capture nor
+    /// promote.
+    fn get_previous_positions(&self, position: &TablePosition) -> Vec<TablePosition> {
+        let mut previous_positions = vec![];
+        let mut add_previous = |piece: usize, origin: u8| {
+            let mut previous = *position;
+            previous.squares[piece] = origin;
+            previous.white_to_move = !position.white_to_move;
+            if self.is_legal(&previous) {
+                previous_positions.push(previous);
+            }
+        };
+        let is_empty = |square: u8| !position.is_occupied(square, self.pieces_count);
+
+        if position.white_to_move {
+            for king_offset in KING_OFFSETS {
+                if let Some(origin) =
+                    offset(position.black_king(), king_offset).filter(|square| is_empty(*square))
+                {
+                    add_previous(1, origin);
+                }
+            }
+            return previous_positions;
+        }
+
+        for piece in std::iter::once(0).chain(2..self.pieces_count) {
+            let destination = position.squares[piece];
+            let kind = if piece == 0 {
+                &PieceTypes::King
+            } else {
+                self.kind(piece)
+            };
+
+            let mut origins = vec![];
+            let mut slide = |directions: &[(i8, i8)]| {
+                for direction in directions {
+                    let mut square = destination;
+                    while let Some(next) =
+                        offset(square, *direction).filter(|square| is_empty(*square))
+                    {
+                        origins.push(next);
+                        square = next;
+                    }
+                }
+            };
+            match kind {
+                PieceTypes::Queen => {
+                    slide(&ROOK_DIRECTIONS);
+                    slide(&BISHOP_DIRECTIONS);
+                }
+                PieceTypes::Rook => slide(&ROOK_DIRECTIONS),
+                PieceTypes::Bishop => slide(&BISHOP_DIRECTIONS),
+                PieceTypes::Knight | PieceTypes::King => {
+                    let offsets = if kind == &PieceTypes::King {
+                        &KING_OFFSETS
+                    } else {
+                        &KNIGHT_OFFSETS
+                    };
+                    origins.extend(
+                        offsets
+                            .iter()
+                            .filter_map(|piece_offset| offset(destination, *piece_offset))
+                            .filter(|square| is_empty(*square)),
+                    );
+                }
+                PieceTypes::Pawn => {
+                    if let Some(origin) =
+                        offset(destination, (-1, 0)).filter(|square| is_empty(*square))
+                    {
+                        origins.push(origin);
+                        let double_step_origin = offset(origin, (-1, 0))
+                            .filter(|square| is_empty(*square) && (8..16).contains(square));
+                        if (24..32).contains(&destination) {
+                            origins.extend(double_step_origin);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            for origin in origins {
+                add_previous(piece, origin);
+            }
+        }
+
+        previous_positions
+    }
+}
+
+/// Get's the square moved from the given one by `(rows, columns)`, if it's on the board.
+fn offset(square: u8, (rows, columns): (i8, i8)) -> Option<u8> {
+    let row = (square / 8) as i8 + rows;
+    let column = (square % 8) as i8 + columns;
+    ((0..8).contains(&row) && (0..8).contains(&column)).then_some((row * 8 + column) as u8)
+}
+
+fn are_adjacent(a: u8, b: u8) -> bool {
+    let (a_row, a_column) = ((a / 8) as i8, (a % 8) as i8);
+    let (b_row, b_column) = ((b / 8) as i8, (b % 8) as i8);
+    (a_row - b_row).abs() <= 1 && (a_column - b_column).abs() <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(table: &EndgameTable, fen: &str) -> Option<DistanceToMate> {
+        table.probe_dtm(&Fen::try_from(fen).unwrap().board)
+    }
+
+    /// Get's the longest distance to mate of the table, in plies, for the player with the pieces.
+    fn longest_win(table: &EndgameTable) -> usize {
+        table
+            .values
+            .iter()
+            .step_by(2)
+            .filter(|value| **value != ILLEGAL)
+            .map(|value| *value as usize)
+            .max()
+            .unwrap_or(0)
+            .saturating_sub(1)
+    }
+
+    #[test]
+    fn queen_endgame_distances_to_mate() {
+        let table = EndgameTable::generate(EndgameMaterial::KingQueen);
+
+        assert_eq!(
+            probe(&table, "k7/8/1K6/8/8/8/8/6Q1 w - - 0 1"),
+            Some(DistanceToMate::Win(1))
+        );
+        assert_eq!(
+            probe(&table, "Q6k/8/6K1/8/8/8/8/8 b - - 0 1"),
+            Some(DistanceToMate::Loss(0))
+        );
+        assert_eq!(
+            probe(&table, "7k/8/8/8/8/8/8/Kq6 w - - 0 1"),
+            Some(DistanceToMate::Draw)
+        );
+        // The longest mate with the queen takes 10 moves.
+        assert_eq!(longest_win(&table), 19);
+    }
+
+    #[test]
+    fn rook_endgame_distances_to_mate() {
+        let table = EndgameTable::generate(EndgameMaterial::KingRook);
+
+        assert_eq!(
+            probe(&table, "k7/8/1K6/8/8/8/8/7R w - - 0 1"),
+            Some(DistanceToMate::Win(1))
+        );
+        assert_eq!(
+            probe(&table, "k7/8/2K5/8/8/8/8/7R w - - 0 1"),
+            Some(DistanceToMate::Win(3))
+        );
+        // The longest mate with the rook takes 16 moves.
+        assert_eq!(longest_win(&table), 31);
+    }
+
+    #[test]
+    fn bishop_and_knight_endgame_distances_to_mate() {
+        let table = EndgameTable::generate(EndgameMaterial::KingBishopKnight);
+
+        assert_eq!(
+            probe(&table, "7k/7B/6K1/4N3/8/8/8/8 w - - 0 1"),
+            Some(DistanceToMate::Win(1))
+        );
+        // The longest mate with the bishop and the knight takes 33 moves.
+        assert_eq!(longest_win(&table), 65);
+    }
+
+    #[test]
+    fn pawn_endgame_movements_mate_the_fastest() {
+        let mut tables = EndgameTables::default();
+        for material in [
+            EndgameMaterial::KingQueen,
+            EndgameMaterial::KingRook,
+            EndgameMaterial::KingPawn,
+        ] {
+            tables.add(EndgameTable::generate(material));
+        }
+        let board = Fen::try_from("8/6P1/8/8/8/8/8/K1k5 w - - 0 1")
+            .unwrap()
+            .board;
+        assert_eq!(tables.probe_dtm(&board), Some(DistanceToMate::Win(15)));
+        assert_eq!(tables.probe_dtz(&board), None);
+
+        let movements = get_tablebase_movements(&tables, &board).unwrap();
+
+        assert!(!movements.is_empty());
+        for movement in movements {
+            let applied_movement = apply_movement(
+                &board,
+                &movement.piece,
+                &movement.destination,
+                movement.promotion,
+            )
+            .unwrap();
+            assert_eq!(
+                tables.probe_dtm(&applied_movement.board),
+                Some(DistanceToMate::Loss(14))
+            );
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+/// How much each cell is worth for a piece on a standard board, from white's side, starting with
+/// the first rank. Black pieces use the same tables mirrored.
+#[rustfmt::skip]
+const PAWN_TABLE: [[i32; 8]; 8] = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  5,  10,  10, -20, -20,  10,  10,   5],
+    [  5,  -5, -10,   0,   0, -10,  -5,   5],
+    [  0,   0,   0,  20,  20,   0,   0,   0],
+    [  5,   5,  10,  25,  25,  10,   5,   5],
+    [ 10,  10,  20,  30,  30,  20,  10,  10],
+    [ 50,  50,  50,  50,  50,  50,  50,  50],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [[i32; 8]; 8] = [
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -20,   0,   5,   5,   0, -20, -40],
+    [-30,   5,  10,  15,  15,  10,   5, -30],
+    [-30,   0,  15,  20,  20,  15,   0, -30],
+    [-30,   5,  15,  20,  20,  15,   5, -30],
+    [-30,   0,  10,  15,  15,  10,   0, -30],
+    [-40, -20,   0,   0,   0,   0, -20, -40],
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [[i32; 8]; 8] = [
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+    [-10,   5,   0,   0,   0,   0,   5, -10],
+    [-10,  10,  10,  10,  10,  10,  10, -10],
+    [-10,   0,  10,  10,  10,  10,   0, -10],
+    [-10,   5,   5,  10,  10,   5,   5, -10],
+    [-10,   0,   5,  10,  10,   5,   0, -10],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [[i32; 8]; 8] = [
+    [  0,   0,   0,   5,   5,   0,   0,   0],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [  5,  10,  10,  10,  10,  10,  10,   5],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [[i32; 8]; 8] = [
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+    [-10,   0,   5,   0,   0,   0,   0, -10],
+    [-10,   5,   5,   5,   5,   5,   0, -10],
+    [  0,   0,   5,   5,   5,   5,   0,  -5],
+    [ -5,   0,   5,   5,   5,   5,   0,  -5],
+    [-10,   0,   5,   5,   5,   5,   0, -10],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+];
+
+#[rustfmt::skip]
+const KING_TABLE: [[i32; 8]; 8] = [
+    [ 20,  30,  10,   0,   0,  10,  30,  20],
+    [ 20,  20,   0,   0,   0,   0,  20,  20],
+    [-10, -20, -20, -20, -20, -20, -20, -10],
+    [-20, -30, -30, -40, -40, -30, -30, -20],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+];
+
+impl Board {
+    /// Get's how many centipawns a piece of the given kind is worth on this board.
+    /// Fairy pieces that are not on the fairy pieces of the board are worth nothing.
+    pub fn piece_value(&self, kind: &PieceTypes) -> i32 {
+        match kind {
+            PieceTypes::Pawn => 100,
+            PieceTypes::Knight => 320,
+            PieceTypes::Bishop => 330,
+            PieceTypes::Rook => 500,
+            PieceTypes::Queen => 900,
+            PieceTypes::King => 0,
+            PieceTypes::Fairy(symbol) => self.fairy_piece(*symbol).map_or(0, FairyPiece::value),
+        }
+    }
+
+    /// Get's how good the position is for the player that moves next, in centipawns.
+    /// It only looks at the material and where each piece stands, so it doesn't see threats.
+    pub fn evaluate(&self) -> i32 {
+        let dimensions = self.dimensions();
+        let is_standard = dimensions == BoardDimensions::STANDARD;
+        let score = |pieces: &[ChessPiece]| -> i32 {
+            pieces
+                .iter()
+                .map(|piece| {
+                    let placement = if is_standard {
+                        standard_placement_value(piece)
+                    } else {
+                        placement_value(piece, &dimensions)
+                    };
+                    self.piece_value(piece.kind()) + placement
+                })
+                .sum()
+        };
+
+        let white = score(&self.white_pieces);
+        let black = score(&self.black_pieces);
+        match self.side_to_move {
+            PieceColors::White => white - black,
+            PieceColors::Black => black - white,
+        }
+    }
+}
+
+/// Get's the value of where the piece stands on a standard board.
+fn standard_placement_value(piece: &ChessPiece) -> i32 {
+    let (row, column) = piece.position();
+    let row = match piece.color() {
+        PieceColors::White => row,
+        PieceColors::Black => 7 - row,
+    };
+
+    let table = match piece.kind() {
+        PieceTypes::Pawn => &PAWN_TABLE,
+        PieceTypes::Knight => &KNIGHT_TABLE,
+        PieceTypes::Bishop => &BISHOP_TABLE,
+        PieceTypes::Rook => &ROOK_TABLE,
+        PieceTypes::Queen | PieceTypes::Fairy(_) => &QUEEN_TABLE,
+        PieceTypes::King => &KING_TABLE,
+    };
+    table[row][column]
+}
+
+/// Get's the value of where the piece stands on a board of any size: pawns are worth more the
+/// further they advance and the other pieces the closer they are to the center.
+fn placement_value(piece: &ChessPiece, dimensions: &BoardDimensions) -> i32 {
+    let (row, column) = piece.position();
+    let (white_back_rank, black_back_rank) = dimensions.back_ranks();
+
+    match piece.kind() {
+        PieceTypes::Pawn => {
+            let advance = match piece.color() {
+                PieceColors::White => row.saturating_sub(white_back_rank),
+                PieceColors::Black => black_back_rank.saturating_sub(row),
+            };
+            advance as i32 * 5
+        }
+        PieceTypes::King => 0,
+        _ => {
+            let center_distance =
+                |index: usize, size: usize| (2 * index as i32 - (size as i32 - 1)).abs();
+            -(center_distance(row, dimensions.ranks) + center_distance(column, dimensions.files))
+                * 2
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use super::*;
+
+use thiserror::Error;
+
+/// The Forsyth–Edwards Notation of the standard starting position.
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Debug, Error)]
+pub enum FenError {
+    #[error("A FEN must have the pieces, side to move, castling and en passant fields.")]
+    MissingFields,
+    #[error("The symbol `{0}` is not a piece.")]
+    InvalidSymbol(char),
+    #[error("The ranks of the FEN have different lengths, or the board is too big.")]
+    InvalidDimensions,
+    #[error("The side to move (`{0}`) must be `w` or `b`.")]
+    InvalidSideToMove(String),
+    #[error("The castling rights (`{0}`) are invalid.")]
+    InvalidCastling(String),
+    #[error("The en passant square (`{0}`) is invalid.")]
+    InvalidEnPassant(String),
+    #[error("The move counter (`{0}`) must be a number.")]
+    InvalidCounter(String),
+    #[error("The position is not valid: {0:?}")]
+    InvalidBoard(Vec<BoardProblem>),
+}
+
+/// A board written in Forsyth–Edwards Notation, along with the move counters it carries.
+/// Castling is written with the `KQkq` letters for the corner rooks, and the en passant field is
+/// the cell the pawn passed over, as usual.
+#[derive(Debug, Clone)]
+pub struct Fen {
+    pub board: Board,
+    /// The amount of halfmoves since the last capture or pawn movement.
+    pub halfmove_clock: usize,
+    /// The number of the current move, that starts at 1 and increases after black moves.
+    pub fullmove_number: usize,
+}
+
+impl TryFrom<&str> for Fen {
+    type Error = FenError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Fen::with_fairy_pieces(value, vec![])
+    }
+}
+
+impl Fen {
+    /// Reads the FEN of a board with the given fairy pieces, which are written with their letters
+    /// like the standard pieces.
+    pub fn with_fairy_pieces(value: &str, fairy_pieces: Vec<FairyPiece>) -> Result<Self, FenError> {
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        let [placement, side, castling, en_passant, counters @ ..] = fields.as_slice() else {
+            return Err(FenError::MissingFields);
+        };
+
+        let mut builder = parse_placement(placement, fairy_pieces)?;
+        let side_to_move = match *side {
+            "w" => PieceColors::White,
+            "b" => PieceColors::Black,
+            _ => return Err(FenError::InvalidSideToMove(side.to_string())),
+        };
+        builder = builder.side_to_move(side_to_move.clone());
+
+        let (white_castling, black_castling) = parse_castling(castling)?;
+        builder = builder
+            .castling(PieceColors::White, white_castling)
+            .castling(PieceColors::Black, black_castling);
+
+        if *en_passant != "-" {
+            let target = BoardPosition::try_from(*en_passant)
+                .map_err(|_| FenError::InvalidEnPassant(en_passant.to_string()))?;
+            let (row, column) = (&target).into();
+            let pawn_row = match side_to_move {
+                PieceColors::White => row.checked_sub(1),
+                PieceColors::Black => Some(row + 1),
+            };
+            let pawn_position = pawn_row
+                .and_then(|row| BoardPosition::try_from((row, column)).ok())
+                .ok_or_else(|| FenError::InvalidEnPassant(en_passant.to_string()))?;
+            builder = builder.en_passant(Some(pawn_position));
+        }
+
+        let parse_counter = |counter: Option<&&str>, default: usize| match counter {
+            Some(counter) => counter
+                .parse::<usize>()
+                .map_err(|_| FenError::InvalidCounter(counter.to_string())),
+            None => Ok(default),
+        };
+        let halfmove_clock = parse_counter(counters.first(), 0)?;
+        let fullmove_number = parse_counter(counters.get(1), 1)?.max(1);
+
+        Ok(Fen {
+            board: builder.build().map_err(FenError::InvalidBoard)?,
+            halfmove_clock,
+            fullmove_number,
+        })
+    }
+}
+
+impl std::fmt::Display for Fen {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let board = &self.board;
+        let ranks: Vec<String> = board
+            .cells
+            .iter()
+            .rev()
+            .map(|row| {
+                let mut rank = String::new();
+                let mut empty_cells = 0;
+                for cell in row {
+                    match cell.piece() {
+                        Some(piece) => {
+                            if empty_cells > 0 {
+                                rank.push_str(&empty_cells.to_string());
+                                empty_cells = 0;
+                            }
+                            rank.push(match piece.color() {
+                                PieceColors::White => piece.kind().symbol(),
+                                PieceColors::Black => piece.kind().symbol().to_ascii_lowercase(),
+                            });
+                        }
+                        None => empty_cells += 1,
+                    }
+                }
+                if empty_cells > 0 {
+                    rank.push_str(&empty_cells.to_string());
+                }
+                rank
+            })
+            .collect();
+
+        let side = match board.side_to_move {
+            PieceColors::White => 'w',
+            PieceColors::Black => 'b',
+        };
+
+        let mut castling = String::new();
+        for (state, right, left) in [
+            (&board.white_castling, 'K', 'Q'),
+            (&board.black_castling, 'k', 'q'),
+        ] {
+            if let Some(state) = state {
+                if state.can_use_right_rook {
+                    castling.push(right);
+                }
+                if state.can_use_left_rook {
+                    castling.push(left);
+                }
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = board
+            .en_passant_position
+            .as_ref()
+            .and_then(|position| {
+                let (row, column) = position.into();
+                let target_row = match board.side_to_move {
+                    PieceColors::White => row + 1,
+                    PieceColors::Black => row.checked_sub(1)?,
+                };
+                BoardPosition::try_from((target_row, column)).ok()
+            })
+            .map_or_else(|| String::from("-"), |target| target.to_string());
+
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            ranks.join("/"),
+            side,
+            castling,
+            en_passant,
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+}
+
+impl Board {
+    /// Get's the Forsyth–Edwards Notation of this board, with the move counters of a new game.
+    pub fn to_fen(&self) -> String {
+        Fen {
+            board: self.clone(),
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+        .to_string()
+    }
+}
+
+impl ChessGame {
+    /// Creates a game that starts on the position of the given Forsyth–Edwards Notation.
+    pub fn from_fen(fen: &str, variant: GameVariant) -> Result<Self, FenError> {
+        let Fen {
+            board,
+            halfmove_clock,
+            fullmove_number,
+        } = Fen::try_from(fen)?;
+
+        Ok(ChessGame::with_clocks(
+            board,
+            variant,
+            halfmove_clock,
+            fullmove_number,
+        ))
+    }
+
+    /// Get's the Forsyth–Edwards Notation of the current position.
+    pub fn to_fen(&self) -> String {
+        Fen {
+            board: self.board().clone(),
+            halfmove_clock: self.halfmove_clock(),
+            fullmove_number: self.fullmove_number(),
+        }
+        .to_string()
+    }
+}
+
+/// Creates a builder with the pieces of the placement field, where ranks are separated by `/`
+/// and start with the last one.
+fn parse_placement(
+    placement: &str,
+    fairy_pieces: Vec<FairyPiece>,
+) -> Result<BoardBuilder, FenError> {
+    let piece_kind = |symbol: char| {
+        PieceTypes::from_symbol(symbol).or_else(|| {
+            fairy_pieces
+                .iter()
+                .find(|piece| piece.symbol() == symbol.to_ascii_uppercase())
+                .map(FairyPiece::kind)
+        })
+    };
+
+    let mut ranks = vec![];
+    for rank in placement.split('/') {
+        let mut cells: Vec<Option<(char, PieceTypes)>> = vec![];
+        let mut empty_cells = String::new();
+        for symbol in rank.chars() {
+            if symbol.is_ascii_digit() {
+                empty_cells.push(symbol);
+                continue;
+            }
+
+            cells.append(&mut parse_empty_cells(&mut empty_cells)?);
+            let kind = piece_kind(symbol).ok_or(FenError::InvalidSymbol(symbol))?;
+            cells.push(Some((symbol, kind)));
+        }
+        cells.append(&mut parse_empty_cells(&mut empty_cells)?);
+        ranks.push(cells);
+    }
+
+    let dimensions = BoardDimensions {
+        files: ranks.first().map_or(0, Vec::len),
+        ranks: ranks.len(),
+    };
+    let is_valid = dimensions.files <= MAX_FILES
+        && dimensions.ranks <= MAX_RANKS
+        && ranks.iter().all(|rank| rank.len() == dimensions.files);
+    if !is_valid {
+        return Err(FenError::InvalidDimensions);
+    }
+
+    let mut builder = BoardBuilder::new(dimensions).fairy_pieces(fairy_pieces.clone());
+    for (row, rank) in ranks.iter().rev().enumerate() {
+        for (column, cell) in rank.iter().enumerate() {
+            let Some((symbol, kind)) = cell else {
+                continue;
+            };
+            let color = if symbol.is_uppercase() {
+                PieceColors::White
+            } else {
+                PieceColors::Black
+            };
+            let position = (row, column)
+                .try_into()
+                .map_err(|_| FenError::InvalidDimensions)?;
+            builder = builder.place(*kind, color, position);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Turns the digits read so far into that amount of empty cells.
+fn parse_empty_cells(digits: &mut String) -> Result<Vec<Option<(char, PieceTypes)>>, FenError> {
+    if digits.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let amount: usize = digits.parse().map_err(|_| FenError::InvalidDimensions)?;
+    digits.clear();
+    if amount > MAX_FILES {
+        return Err(FenError::InvalidDimensions);
+    }
+    Ok(vec![None; amount])
+}
+
+/// Get's the castling rights of white and black from the castling field.
+fn parse_castling(
+    castling: &str,
+) -> Result<(Option<CastlingState>, Option<CastlingState>), FenError> {
+    if castling == "-" {
+        return Ok((None, None));
+    }
+
+    let mut white: Option<CastlingState> = None;
+    let mut black: Option<CastlingState> = None;
+    for symbol in castling.chars() {
+        let state = if symbol.is_uppercase() {
+            &mut white
+        } else {
+            &mut black
+        };
+        let state = state.get_or_insert(CastlingState {
+            can_use_left_rook: false,
+            can_use_right_rook: false,
+        });
+        match symbol.to_ascii_lowercase() {
+            'k' => state.can_use_right_rook = true,
+            'q' => state.can_use_left_rook = true,
+            _ => return Err(FenError::InvalidCastling(castling.to_string())),
+        }
+    }
+
+    Ok((white, black))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fairy_pieces_are_read_only_if_given() {
+        let board = get_capablanca_starting_board();
+        let fen = Fen {
+            board: board.clone(),
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+        .to_string();
+        assert_eq!(
+            fen,
+            "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1"
+        );
+
+        assert!(matches!(
+            Fen::try_from(fen.as_str()),
+            Err(FenError::InvalidSymbol('a'))
+        ));
+        let read_board = Fen::with_fairy_pieces(&fen, board.fairy_pieces.clone())
+            .unwrap()
+            .board;
+        assert_eq!(read_board.to_string(), board.to_string());
+        assert_eq!(read_board.fairy_pieces, board.fairy_pieces);
+    }
+}
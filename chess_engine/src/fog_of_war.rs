@@ -0,0 +1,162 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// The board as seen by one of the players of a fog of war game.
+/// Cells the player can't see are `None`, so the opponent pieces on them are never revealed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FogOfWarView {
+    pub color: PieceColors,
+    pub pieces: Vec<ChessPiece>,
+    pub visible_opponent_pieces: Vec<ChessPiece>,
+    pub visible_positions: Vec<BoardPosition>,
+    pub en_passant_position: Option<BoardPosition>,
+    pub castling: Option<CastlingState>,
+    pub side_to_move: PieceColors,
+    pub cells: Vec<Vec<Option<ChessCell>>>,
+}
+
+impl FogOfWarView {
+    /// Computes what the player of the given color can see of the board.
+    pub fn new(board: &Board, color: &PieceColors) -> Self {
+        let visible_positions = board.visible_positions(color);
+
+        let cells = board
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(row, cells_row)| {
+                cells_row
+                    .iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        let position: BoardPosition = (row, column).try_into().ok()?;
+                        if visible_positions.contains(&position) {
+                            Some(cell.clone())
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let visible_opponent_pieces = board
+            .get_pieces_from(&color.opponent())
+            .iter()
+            .filter(|piece| visible_positions.contains(piece.board_position()))
+            .cloned()
+            .collect();
+
+        let en_passant_position = board
+            .en_passant_position
+            .clone()
+            .filter(|position| visible_positions.contains(position));
+
+        FogOfWarView {
+            color: color.clone(),
+            pieces: board.get_pieces_from(color).clone(),
+            visible_opponent_pieces,
+            visible_positions,
+            en_passant_position,
+            castling: board.get_castling_state(color).clone(),
+            side_to_move: board.side_to_move.clone(),
+            cells,
+        }
+    }
+}
+
+/// Moves a piece following the fog of war rules.
+/// There's no such thing as check in fog of war, kings can move into attacked cells and pieces
+/// can ignore threats to their king. The game is won by capturing the opponent king.
+pub fn move_piece_fog_of_war(
+    movement: BoardMovement,
+    board: &mut Board,
+) -> Result<MoveRecord, MovementError> {
+    let BoardMovement {
+        piece,
+        destination,
+        promotion,
+    } = movement;
+    let piece_color = piece.color().clone();
+
+    if piece_color != board.side_to_move {
+        return Err(MovementError::NotYourTurn);
+    }
+
+    if let Some(p) = board.get_piece(&destination) {
+        if p.color() == &piece_color {
+            return Err(MovementError::DestinationCellOccupied);
+        }
+    }
+
+    let castle_direction = get_castle_direction(board, &piece, &destination)?;
+    if castle_direction.is_none()
+        && !board
+            .get_pseudo_legal_positions(&piece)
+            .contains(&destination)
+    {
+        return Err(MovementError::DestinationDoesntFollowMovementPattern);
+    }
+
+    let promotion = get_promotion(board, &piece, &destination, promotion)?;
+    let mut moved_board = board.clone();
+    let captured_piece =
+        moved_board.move_piece(piece.clone(), &destination, castle_direction, promotion)?;
+
+    let captures_king = matches!(&captured_piece, Some(p) if p.kind() == &PieceTypes::King);
+    let opponent_can_move = moved_board
+        .get_pieces_from(&piece_color.opponent())
+        .iter()
+        .any(|p| !moved_board.get_pseudo_legal_positions(p).is_empty());
+
+    let outcome = if captures_king {
+        MovementSuccess::KingCaptureMovement
+    } else if !opponent_can_move {
+        MovementSuccess::StalemateMovement
+    } else {
+        MovementSuccess::NormalMovement
+    };
+
+    let applied_movement = AppliedMovement {
+        board: moved_board,
+        captured_piece,
+        castling: castle_direction,
+        promotion,
+    };
+    let record = MoveRecord::new(board, piece, destination, &applied_movement, outcome);
+    *board = applied_movement.board;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn king_can_castle_in_fog_of_war() {
+        let mut board = get_starting_board();
+        // Clears the cells between the white king and its right rook.
+        for position in ["f1", "g1"] {
+            let position: BoardPosition = position.try_into().unwrap();
+            let (row, column) = (&position).into();
+            board.cells[row][column] = ChessCell::none();
+            board
+                .white_pieces
+                .retain(|piece| piece.board_position() != &position);
+        }
+        let movement = BoardMovement {
+            piece: board.get_piece(&"e1".try_into().unwrap()).unwrap(),
+            destination: "g1".try_into().unwrap(),
+            promotion: None,
+        };
+
+        move_piece_fog_of_war(movement, &mut board).unwrap();
+
+        let king = board.get_piece(&"g1".try_into().unwrap()).unwrap();
+        let rook = board.get_piece(&"f1".try_into().unwrap()).unwrap();
+        assert_eq!(king.kind(), &PieceTypes::King);
+        assert_eq!(rook.kind(), &PieceTypes::Rook);
+        assert!(board.get_piece(&"h1".try_into().unwrap()).is_none());
+    }
+}
@@ -0,0 +1,434 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The identifier of a node on a game tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NodeId(usize);
+
+/// A position of a game tree, with the movement that led to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameNode {
+    /// The movement that led to this position, or `None` on the root.
+    record: Option<MoveRecord>,
+    board: Board,
+    parent: Option<NodeId>,
+    /// The positions played from this one. The first one is the main line and the rest are
+    /// variations.
+    children: Vec<NodeId>,
+    /// The amount of movements played from the root to get to this position.
+    ply: usize,
+    /// The notes on the movement. On the root, the comment is about the whole game.
+    annotations: Annotations,
+}
+
+impl GameNode {
+    pub fn record(&self) -> Option<&MoveRecord> {
+        self.record.as_ref()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    pub fn annotations(&self) -> &Annotations {
+        &self.annotations
+    }
+
+    /// Checks if no movement can be played after this one.
+    fn is_game_over(&self) -> bool {
+        self.record.as_ref().map_or(false, |record| {
+            record.outcome != MovementSuccess::NormalMovement
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GameTreeError {
+    #[error("The game tree doesn't start on a root position.")]
+    MissingRoot,
+    #[error("The cursor is not on a node of the game tree.")]
+    InvalidCursor,
+    #[error("The node {0} is not linked to its parent, or comes before it.")]
+    InvalidNode(usize),
+}
+
+/// A game with every line that was explored on it, and a cursor on the position being looked at.
+/// Movements are played from the cursor: playing a new movement adds a variation, and playing one
+/// that was already played just moves the cursor to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedGameTree")]
+pub struct GameTree {
+    variant: GameVariant,
+    nodes: Vec<GameNode>,
+    cursor: NodeId,
+}
+
+/// A deserialized game tree whose nodes may not be linked correctly.
+#[derive(Deserialize)]
+struct UncheckedGameTree {
+    variant: GameVariant,
+    nodes: Vec<GameNode>,
+    cursor: NodeId,
+}
+
+/// Checks that every node comes after its parent and is one of its children, so walking the tree
+/// always ends.
+impl TryFrom<UncheckedGameTree> for GameTree {
+    type Error = GameTreeError;
+
+    fn try_from(tree: UncheckedGameTree) -> Result<Self, Self::Error> {
+        let UncheckedGameTree {
+            variant,
+            nodes,
+            cursor,
+        } = tree;
+
+        match nodes.first() {
+            Some(root) if root.parent.is_none() && root.ply == 0 => {}
+            _ => return Err(GameTreeError::MissingRoot),
+        }
+        if cursor.0 >= nodes.len() {
+            return Err(GameTreeError::InvalidCursor);
+        }
+
+        for (index, node) in nodes.iter().enumerate().skip(1) {
+            let is_linked = node.parent.map_or(false, |NodeId(parent)| {
+                parent < index
+                    && nodes[parent].ply + 1 == node.ply
+                    && nodes[parent].children.contains(&NodeId(index))
+            });
+            if !is_linked {
+                return Err(GameTreeError::InvalidNode(index));
+            }
+        }
+        for (index, node) in nodes.iter().enumerate() {
+            let mut children = node.children.clone();
+            children.sort_by_key(|NodeId(child)| *child);
+            children.dedup();
+            let are_linked = children.len() == node.children.len()
+                && children.iter().all(|NodeId(child)| {
+                    nodes
+                        .get(*child)
+                        .map_or(false, |child| child.parent == Some(NodeId(index)))
+                });
+            if !are_linked {
+                return Err(GameTreeError::InvalidNode(index));
+            }
+        }
+
+        Ok(GameTree {
+            variant,
+            nodes,
+            cursor,
+        })
+    }
+}
+
+impl Default for GameTree {
+    fn default() -> Self {
+        GameTree::new(get_starting_board(), GameVariant::Standard)
+    }
+}
+
+impl GameTree {
+    const ROOT: NodeId = NodeId(0);
+
+    /// Creates a tree that starts on the given board.
+    pub fn new(board: Board, variant: GameVariant) -> Self {
+        GameTree {
+            variant,
+            nodes: vec![GameNode {
+                record: None,
+                board,
+                parent: None,
+                children: vec![],
+                ply: 0,
+                annotations: Annotations::default(),
+            }],
+            cursor: Self::ROOT,
+        }
+    }
+
+    pub fn variant(&self) -> GameVariant {
+        self.variant
+    }
+
+    pub fn root(&self) -> NodeId {
+        Self::ROOT
+    }
+
+    pub fn cursor(&self) -> NodeId {
+        self.cursor
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&GameNode> {
+        self.nodes.get(id.0)
+    }
+
+    /// Get's the node the cursor is on.
+    pub fn current(&self) -> &GameNode {
+        &self.nodes[self.cursor.0]
+    }
+
+    /// Get's the notes of the node the cursor is on, to change them.
+    pub fn annotations_mut(&mut self) -> &mut Annotations {
+        &mut self.nodes[self.cursor.0].annotations
+    }
+
+    /// Get's the board on the cursor.
+    pub fn board(&self) -> &Board {
+        self.current().board()
+    }
+
+    /// Get's the nodes from the root to the cursor, both included.
+    pub fn line(&self) -> Vec<NodeId> {
+        let mut line = vec![self.cursor];
+        while let Some(parent) = self.nodes[line[line.len() - 1].0].parent {
+            line.push(parent);
+        }
+        line.reverse();
+        line
+    }
+
+    /// Get's the nodes of the main line, following the first child from the root.
+    pub fn mainline(&self) -> Vec<NodeId> {
+        let mut line = vec![Self::ROOT];
+        while let Some(child) = self.nodes[line[line.len() - 1].0].children.first() {
+            line.push(*child);
+        }
+        line
+    }
+
+    /// Plays the movement on the cursor and moves the cursor to the resulting position.
+    /// If the movement was already played from there, the cursor moves to it instead of adding it
+    /// again.
+    pub fn play(&mut self, movement: BoardMovement) -> Result<NodeId, GameError> {
+        let current = self.current();
+        if current.is_game_over() {
+            return Err(GameError::GameOver);
+        }
+
+        let mut board = current.board.clone();
+        let record = self.variant.move_piece(movement, &mut board)?;
+
+        let existing = current.children.iter().copied().find(|child| {
+            self.nodes[child.0].record.as_ref().map_or(false, |played| {
+                played.from == record.from
+                    && played.to == record.to
+                    && played.promotion == record.promotion
+            })
+        });
+        if let Some(child) = existing {
+            self.cursor = child;
+            return Ok(child);
+        }
+
+        let id = NodeId(self.nodes.len());
+        let ply = current.ply + 1;
+        self.nodes.push(GameNode {
+            record: Some(record),
+            board,
+            parent: Some(self.cursor),
+            children: vec![],
+            ply,
+            annotations: Annotations::default(),
+        });
+        self.nodes[self.cursor.0].children.push(id);
+        self.cursor = id;
+
+        Ok(id)
+    }
+
+    /// Moves the cursor to the given node.
+    /// Returns false if the node is not on this tree.
+    pub fn go_to(&mut self, id: NodeId) -> bool {
+        if id.0 < self.nodes.len() {
+            self.cursor = id;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves the cursor one movement forward on the main line of the current position.
+    /// Returns false if there's no movement after the cursor.
+    pub fn forward(&mut self) -> bool {
+        match self.current().children.first() {
+            Some(child) => {
+                self.cursor = *child;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor one movement back.
+    /// Returns false if the cursor is on the root.
+    pub fn back(&mut self) -> bool {
+        match self.current().parent {
+            Some(parent) => {
+                self.cursor = parent;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor to the root.
+    pub fn to_start(&mut self) {
+        self.cursor = Self::ROOT;
+    }
+
+    /// Moves the cursor forward until the end of the current line.
+    pub fn to_end(&mut self) {
+        while self.forward() {}
+    }
+
+    /// Moves the cursor to the given ply of the current line, going back on it or forward on its
+    /// main line.
+    /// Returns false, leaving the cursor on the last position, if the line is not that long.
+    pub fn go_to_ply(&mut self, ply: usize) -> bool {
+        while self.current().ply > ply {
+            self.back();
+        }
+        while self.current().ply < ply {
+            if !self.forward() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Makes the variation the cursor is on the main line of the position it branched from.
+    /// Calling it again keeps promoting the line until it's the main line of the game.
+    /// Returns false if the cursor is on the main line.
+    pub fn promote_variation(&mut self) -> bool {
+        let mut node = self.cursor;
+        while let Some(parent) = self.nodes[node.0].parent {
+            let siblings = &mut self.nodes[parent.0].children;
+            if let Some(index) = siblings.iter().position(|child| *child == node) {
+                if index > 0 {
+                    let variation = siblings.remove(index);
+                    siblings.insert(0, variation);
+                    return true;
+                }
+            }
+            node = parent;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(tree: &mut GameTree, from: &str, to: &str) -> NodeId {
+        let piece = tree.board().get_piece(&from.try_into().unwrap()).unwrap();
+        tree.play(BoardMovement {
+            piece,
+            destination: to.try_into().unwrap(),
+            promotion: None,
+        })
+        .unwrap()
+    }
+
+    /// Plays 1. e4 e5 2. Nf3 on the main line and 1... c5 as a variation.
+    fn sicilian_variation() -> (GameTree, [NodeId; 4]) {
+        let mut tree = GameTree::default();
+        let e4 = play(&mut tree, "e2", "e4");
+        let e5 = play(&mut tree, "e7", "e5");
+        let nf3 = play(&mut tree, "g1", "f3");
+        tree.go_to(e4);
+        let c5 = play(&mut tree, "c7", "c5");
+        (tree, [e4, e5, nf3, c5])
+    }
+
+    #[test]
+    fn moves_forward_and_back_on_the_main_line() {
+        let (mut tree, [e4, e5, nf3, c5]) = sicilian_variation();
+        assert_eq!(tree.mainline(), vec![tree.root(), e4, e5, nf3]);
+        assert_eq!(tree.line(), vec![tree.root(), e4, c5]);
+
+        assert!(!tree.forward());
+        assert!(tree.back());
+        assert_eq!(tree.cursor(), e4);
+        assert!(tree.forward());
+        assert_eq!(tree.cursor(), e5);
+
+        tree.to_start();
+        assert!(!tree.back());
+        tree.to_end();
+        assert_eq!(tree.cursor(), nf3);
+
+        // Playing a movement that was already played follows it instead of adding a node.
+        tree.go_to(e4);
+        assert_eq!(play(&mut tree, "e7", "e5"), e5);
+        assert_eq!(tree.node(e4).unwrap().children(), &[e5, c5]);
+    }
+
+    #[test]
+    fn goes_to_a_ply_of_the_current_line() {
+        let (mut tree, [e4, e5, nf3, c5]) = sicilian_variation();
+
+        assert!(tree.go_to_ply(0));
+        assert_eq!(tree.cursor(), tree.root());
+        assert!(tree.go_to_ply(2));
+        assert_eq!(tree.cursor(), e5);
+
+        tree.go_to(c5);
+        assert!(tree.go_to_ply(1));
+        assert_eq!(tree.cursor(), e4);
+        assert!(!tree.go_to_ply(5));
+        assert_eq!(tree.cursor(), nf3);
+    }
+
+    #[test]
+    fn promotes_variations_to_the_main_line() {
+        let (mut tree, [e4, e5, nf3, c5]) = sicilian_variation();
+
+        assert!(tree.promote_variation());
+        assert_eq!(tree.mainline(), vec![tree.root(), e4, c5]);
+        assert!(!tree.promote_variation());
+
+        tree.go_to(nf3);
+        assert!(tree.promote_variation());
+        assert_eq!(tree.mainline(), vec![tree.root(), e4, e5, nf3]);
+    }
+
+    #[test]
+    fn only_linked_trees_are_deserialized() {
+        let (tree, _) = sicilian_variation();
+        let json = serde_json::to_value(&tree).unwrap();
+        let read_tree: GameTree = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(read_tree.mainline(), tree.mainline());
+        assert_eq!(read_tree.cursor(), tree.cursor());
+
+        let mut bad_cursor = json.clone();
+        bad_cursor["cursor"] = 10.into();
+        assert!(serde_json::from_value::<GameTree>(bad_cursor).is_err());
+
+        let mut cycle = json.clone();
+        cycle["nodes"][1]["parent"] = 2.into();
+        cycle["nodes"][2]["parent"] = 1.into();
+        assert!(serde_json::from_value::<GameTree>(cycle).is_err());
+
+        let mut unknown_child = json;
+        unknown_child["nodes"][0]["children"] = serde_json::json!([1, 7]);
+        assert!(serde_json::from_value::<GameTree>(unknown_child).is_err());
+    }
+}
@@ -2,112 +2,116 @@ use super::*;
 
 /// Get's the initial board of the game.
 pub fn get_starting_board() -> Board {
-    let (white_pieces, black_pieces, cells) = generate_starting_chess_cells();
-    let white_king_position = "e1".try_into().unwrap();
-    let black_king_position = "e8".try_into().unwrap();
-    Board::new(
-        cells,
-        white_pieces,
-        black_pieces,
-        white_king_position,
-        black_king_position,
-        None,
-        None,
-        Some(CastlingState {
-            can_use_left_rook: true,
-            can_use_right_rook: true,
-        }),
-        Some(CastlingState {
-            can_use_left_rook: true,
-            can_use_right_rook: true,
-        }),
+    generate_starting_board(
+        BoardDimensions::STANDARD,
+        &[
+            PieceTypes::Rook,
+            PieceTypes::Knight,
+            PieceTypes::Bishop,
+            PieceTypes::Queen,
+            PieceTypes::King,
+            PieceTypes::Bishop,
+            PieceTypes::Knight,
+            PieceTypes::Rook,
+        ],
+        vec![],
+        true,
     )
 }
 
-fn generate_starting_chess_cells() -> (Vec<ChessPiece>, Vec<ChessPiece>, Vec<Vec<ChessCell>>) {
-    let white_pawns: Vec<ChessCell> = ('a'..='h')
-        .map(|c| format!("{}2", c))
-        .map(|c| c.as_str().try_into().unwrap())
-        .map(|position| ChessPiece::new(PieceTypes::Pawn, position, PieceColors::White))
-        .map(ChessCell::some)
-        .collect();
-    let black_pawns: Vec<ChessCell> = ('a'..='h')
-        .map(|c| format!("{}7", c))
-        .map(|c| c.as_str().try_into().unwrap())
-        .map(|position| ChessPiece::new(PieceTypes::Pawn, position, PieceColors::Black))
-        .map(ChessCell::some)
-        .collect();
-    let mut white_space: Vec<Vec<ChessCell>> = (3..=6)
-        .map(|_| {
-            ('a'..='h')
-                .map(|_| ChessCell::none())
-                .collect::<Vec<ChessCell>>()
-        })
-        .collect();
-
-    let mut white_back_rank: Vec<ChessCell> = ('a'..='h')
-        .map(|c| {
-            let position: BoardPosition = format!("{}1", c).as_str().try_into().unwrap();
-            match c {
-                'a' => (position, PieceTypes::Rook),
-                'b' => (position, PieceTypes::Knight),
-                'c' => (position, PieceTypes::Bishop),
-                'd' => (position, PieceTypes::Queen),
-                'e' => (position, PieceTypes::King),
-                'f' => (position, PieceTypes::Bishop),
-                'g' => (position, PieceTypes::Knight),
-                'h' => (position, PieceTypes::Rook),
-                _ => unreachable!(),
-            }
-        })
-        .map(|(pos, kind)| ChessPiece::new(kind, pos, PieceColors::White))
-        .map(ChessCell::some)
-        .collect();
-
-    let mut black_back_rank: Vec<ChessCell> = ('a'..='h')
-        .map(|c| {
-            let position: BoardPosition = format!("{}1", c).as_str().try_into().unwrap();
-            match c {
-                'a' => (position, PieceTypes::Rook),
-                'b' => (position, PieceTypes::Knight),
-                'c' => (position, PieceTypes::Bishop),
-                'd' => (position, PieceTypes::Queen),
-                'e' => (position, PieceTypes::King),
-                'f' => (position, PieceTypes::Bishop),
-                'g' => (position, PieceTypes::Knight),
-                'h' => (position, PieceTypes::Rook),
-                _ => unreachable!(),
-            }
-        })
-        .map(|(pos, kind)| ChessPiece::new(kind, pos, PieceColors::White))
-        .map(ChessCell::some)
-        .collect();
+/// Get's the initial board of a Capablanca chess game.
+/// It's played on a 10x8 board and each player has an archbishop and a chancellor.
+pub fn get_capablanca_starting_board() -> Board {
+    generate_starting_board(
+        BoardDimensions::CAPABLANCA,
+        &[
+            PieceTypes::Rook,
+            PieceTypes::Knight,
+            PieceTypes::ARCHBISHOP,
+            PieceTypes::Bishop,
+            PieceTypes::Queen,
+            PieceTypes::King,
+            PieceTypes::Bishop,
+            PieceTypes::CHANCELLOR,
+            PieceTypes::Knight,
+            PieceTypes::Rook,
+        ],
+        vec![FairyPiece::ARCHBISHOP, FairyPiece::CHANCELLOR],
+        true,
+    )
+}
 
-    white_back_rank.append(&mut white_pawns.clone());
-    black_back_rank.append(&mut black_pawns.clone());
+/// Get's the initial board of a Gardner minichess game.
+/// It's played on a 5x5 board without castling.
+pub fn get_gardner_starting_board() -> Board {
+    generate_starting_board(
+        BoardDimensions::GARDNER,
+        &[
+            PieceTypes::Rook,
+            PieceTypes::Knight,
+            PieceTypes::Bishop,
+            PieceTypes::Queen,
+            PieceTypes::King,
+        ],
+        vec![],
+        false,
+    )
+}
 
-    let white_pieces = white_back_rank
-        .clone()
-        .into_iter()
-        .map(|cell| cell.0.unwrap())
-        .collect();
-    let black_pieces = black_back_rank
-        .clone()
-        .into_iter()
-        .map(|cell| cell.0.unwrap())
-        .collect();
-    (
-        white_pieces,
-        black_pieces,
-        vec![
-            white_back_rank,
-            white_pawns,
-            white_space.pop().unwrap(),
-            white_space.pop().unwrap(),
-            white_space.pop().unwrap(),
-            white_space.pop().unwrap(),
-            black_pawns,
-            black_back_rank,
+/// Get's the initial board of a Los Alamos chess game.
+/// It's played on a 6x6 board without bishops nor castling.
+pub fn get_los_alamos_starting_board() -> Board {
+    generate_starting_board(
+        BoardDimensions::LOS_ALAMOS,
+        &[
+            PieceTypes::Rook,
+            PieceTypes::Knight,
+            PieceTypes::Queen,
+            PieceTypes::King,
+            PieceTypes::Knight,
+            PieceTypes::Rook,
         ],
+        vec![],
+        false,
     )
 }
+
+/// Generates a board with the given back rank for both players and a full rank of pawns in front
+/// of it. Black pieces mirror the white ones on the opposite side of the board.
+fn generate_starting_board(
+    dimensions: BoardDimensions,
+    back_rank: &[PieceTypes],
+    fairy_pieces: Vec<FairyPiece>,
+    can_castle: bool,
+) -> Board {
+    let (white_back_rank, black_back_rank) = dimensions.back_ranks();
+    let castling = can_castle.then_some(CastlingState {
+        can_use_left_rook: true,
+        can_use_right_rook: true,
+    });
+    let mut builder = BoardBuilder::new(dimensions)
+        .fairy_pieces(fairy_pieces)
+        .castling(PieceColors::White, castling.clone())
+        .castling(PieceColors::Black, castling);
+
+    for (color, back_row, pawn_row) in [
+        (PieceColors::White, white_back_rank, white_back_rank + 1),
+        (PieceColors::Black, black_back_rank, black_back_rank - 1),
+    ] {
+        let back_rank_pieces = back_rank
+            .iter()
+            .enumerate()
+            .map(|(column, kind)| (back_row, column, *kind));
+        let pawns = (0..dimensions.files).map(|column| (pawn_row, column, PieceTypes::Pawn));
+
+        for (row, column, kind) in back_rank_pieces.chain(pawns) {
+            if let Ok(position) = (row, column).try_into() {
+                builder = builder.place(kind, color.clone(), position);
+            }
+        }
+    }
+
+    builder
+        .build()
+        .expect("The starting boards should always be valid!")
+}
@@ -1,26 +1,78 @@
+mod annotations;
+mod attacks;
+mod board_builder;
+mod board_diagram;
+mod board_validation;
+mod chess_game;
+mod endgame_tables;
+mod evaluation;
+mod fen;
+mod fog_of_war;
+mod game_tree;
 mod get_starting_board;
+mod mate_solver;
+mod material;
 mod model;
 mod move_piece;
+mod opening_book;
+mod pgn;
+mod piece_definitions;
+mod san;
+mod search;
+mod skill_level;
+#[cfg(feature = "syzygy")]
+mod syzygy;
+mod tablebase;
+mod time_manager;
+mod transposition_table;
+mod uci;
+mod uci_move;
 mod valid_movements_positions;
-
+#[cfg(feature = "wasm")]
+mod wasm;
+mod zobrist;
+
+pub use self::annotations::*;
+pub use self::board_builder::*;
+pub use self::board_diagram::*;
+pub use self::board_validation::*;
+pub use self::chess_game::*;
+pub use self::endgame_tables::*;
+pub use self::fen::*;
+pub use self::fog_of_war::*;
+pub use self::game_tree::*;
 pub use self::get_starting_board::*;
+pub use self::mate_solver::*;
 pub use self::model::*;
 pub use self::move_piece::*;
+pub use self::opening_book::*;
+pub use self::pgn::*;
+pub use self::piece_definitions::*;
+pub use self::san::*;
+pub use self::search::*;
+pub use self::skill_level::*;
+#[cfg(feature = "syzygy")]
+pub use self::syzygy::*;
+pub use self::tablebase::*;
+pub use self::time_manager::*;
+pub use self::transposition_table::*;
+pub use self::uci::*;
+pub use self::uci_move::*;
 pub use self::valid_movements_positions::*;
+#[cfg(feature = "wasm")]
+pub use self::wasm::*;
 
 /// Check if the given chess piece is a pawn and is on the starting position of the pawn color.
 /// The starting position for white pawns is the second rank.
-/// The starting position for black pawns is the seventh rank.
-pub fn is_pawn_on_starting_position(piece: &ChessPiece) -> bool {
-    let white_starting_row: ChessRank = 2.try_into().unwrap();
-    let black_starting_row: ChessRank = 7.try_into().unwrap();
-
+/// The starting position for black pawns is the rank before the last one.
+pub fn is_pawn_on_starting_position(piece: &ChessPiece, dimensions: &BoardDimensions) -> bool {
+    let (white_back_rank, black_back_rank) = dimensions.back_ranks();
     let (row, _) = piece.position();
 
     if let PieceTypes::Pawn = piece.kind() {
         match piece.color() {
-            PieceColors::Black => black_starting_row.to_index() == row,
-            PieceColors::White => white_starting_row.to_index() == row,
+            PieceColors::Black => black_back_rank.checked_sub(1) == Some(row),
+            PieceColors::White => white_back_rank + 1 == row,
         }
     } else {
         false
@@ -28,172 +80,71 @@ pub fn is_pawn_on_starting_position(piece: &ChessPiece) -> bool {
 }
 
 /// Checks if the given chess piece is a king and is on the starting positions of the king color.
-/// The starting position for white kings is e1.
-/// The starting position for black kings is e8.
-pub fn is_king_on_starting_position(piece: &ChessPiece) -> bool {
-    let white_king_pos: BoardPosition = "e1".try_into().unwrap();
-    let black_king_pos: BoardPosition = "e8".try_into().unwrap();
-
-    let position = piece.position().try_into().unwrap();
+/// The starting position for kings is the middle file of their back rank, e1 and e8 on a
+/// standard board.
+pub fn is_king_on_starting_position(piece: &ChessPiece, dimensions: &BoardDimensions) -> bool {
+    let (white_back_rank, black_back_rank) = dimensions.back_ranks();
+    let (row, column) = piece.position();
 
     if let PieceTypes::King = piece.kind() {
-        match piece.color() {
-            PieceColors::Black => black_king_pos == position,
-            PieceColors::White => white_king_pos == position,
-        }
+        let back_rank = match piece.color() {
+            PieceColors::Black => black_back_rank,
+            PieceColors::White => white_back_rank,
+        };
+        row == back_rank && column == dimensions.king_file()
     } else {
         false
     }
 }
 
-/// Checks if the king at a given position wants to castle to the left
-pub fn check_castle_left(origin: &BoardPosition, destination: &BoardPosition) -> bool {
-    let (_, org_column) = origin.into();
-    let (_, dest_column) = destination.into();
+/// Get's the file indices the king and the rook end on after castling to the given direction.
+/// Returns a tuple of the form `(king_file, rook_file)`.
+pub fn castling_files(
+    direction: &ChessBoardDirections,
+    dimensions: &BoardDimensions,
+) -> (usize, usize) {
+    match direction {
+        ChessBoardDirections::Right => (
+            dimensions.files.saturating_sub(2),
+            dimensions.files.saturating_sub(3),
+        ),
+        _ => (2, 3),
+    }
+}
 
-    dest_column < org_column
+/// Checks if the king at a given position wants to castle to the left
+pub fn check_castle_left(
+    origin: &BoardPosition,
+    destination: &BoardPosition,
+    dimensions: &BoardDimensions,
+) -> bool {
+    let (org_row, _) = origin.into();
+    let (dest_row, dest_column) = destination.into();
+    let (king_file, _) = castling_files(&ChessBoardDirections::Left, dimensions);
+
+    org_row == dest_row && dest_column == king_file
 }
 
 /// Checks if the king at a given position wants to castle to the right
-pub fn check_castle_right(origin: &BoardPosition, destination: &BoardPosition) -> bool {
-    let (_, org_column) = origin.into();
-    let (_, dest_column) = destination.into();
-
-    dest_column > org_column
+pub fn check_castle_right(
+    origin: &BoardPosition,
+    destination: &BoardPosition,
+    dimensions: &BoardDimensions,
+) -> bool {
+    let (org_row, _) = origin.into();
+    let (dest_row, dest_column) = destination.into();
+    let (king_file, _) = castling_files(&ChessBoardDirections::Right, dimensions);
+
+    org_row == dest_row && dest_column == king_file
 }
 
 /// Get's all the possible movements, valid or invalid that a given piece can make.
 /// The return value is a collections of paths the given piece can make. This makes easier for
 /// checking for collisions down the line.
-pub fn get_movement_pattern(piece: &ChessPiece) -> Vec<BoardPath> {
-    let kind = *piece.kind();
-    let (row, column) = piece.position();
-
-    match kind {
-        PieceTypes::Pawn => match (piece.color(), is_pawn_on_starting_position(piece)) {
-            (PieceColors::Black, true) => {
-                let positions = vec![(row - 1, column), (row - 2, column)];
-                let path: Vec<BoardPosition> = positions
-                    .into_iter()
-                    .map(|s| s.try_into())
-                    .filter(Result::is_ok)
-                    .map(Result::unwrap)
-                    .collect();
-                vec![BoardPath(path)]
-            }
-            (PieceColors::Black, false) => {
-                let position = (row - 1, column);
-                if let Ok(position) = position.try_into() {
-                    vec![BoardPath(vec![position])]
-                } else {
-                    vec![BoardPath(vec![])]
-                }
-            }
-            (PieceColors::White, true) => {
-                let positions = vec![(row + 1, column), (row + 2, column)];
-                let path: Vec<BoardPosition> = positions
-                    .into_iter()
-                    .map(|s| s.try_into())
-                    .filter(Result::is_ok)
-                    .map(Result::unwrap)
-                    .collect();
-                vec![BoardPath(path)]
-            }
-            (PieceColors::White, false) => {
-                let position = (row + 1, column);
-                if let Ok(position) = position.try_into() {
-                    vec![BoardPath(vec![position])]
-                } else {
-                    vec![BoardPath(vec![])]
-                }
-            }
-        },
-        PieceTypes::Rook => rook_movement_pattern(row, column),
-        PieceTypes::Knight => pattern_from_vec(
-            vec![
-                (2, 1),
-                (1, 2),
-                (2, -1),
-                (1, -2),
-                (-1, -2),
-                (-2, -1),
-                (-2, 1),
-                (-1, 2),
-            ],
-            row,
-            column,
-        ),
-        PieceTypes::Bishop => bishop_movement_pattern(row, column),
-        PieceTypes::Queen => {
-            let mut r_pattern = rook_movement_pattern(row, column);
-            let mut b_pattern = bishop_movement_pattern(row, column);
-
-            r_pattern.append(&mut b_pattern);
-            r_pattern
-        }
-        PieceTypes::King => pattern_from_vec(
-            vec![
-                (1, -1),
-                (1, 0),
-                (1, 1),
-                (0, -1),
-                (0, 1),
-                (-1, -1),
-                (-1, 1),
-                (-1, 0),
-            ],
-            row,
-            column,
-        ),
-    }
-}
-
-/// Creates a pattern of movement from a vec of positions.
-/// Each position assumes that the piece is located at 0,0. Negative positions are allowed.
-fn pattern_from_vec(vec: Vec<(isize, isize)>, row: usize, column: usize) -> Vec<BoardPath> {
-    vec.into_iter()
-        .map(|(r, c): (isize, isize)| (row as isize + r, column as isize + c))
-        .filter_map(|p| p.try_into().ok())
-        .map(|p: BoardPosition| vec![p])
-        .map(BoardPath::from)
-        .collect()
-}
-
-fn rook_movement_pattern(row: usize, column: usize) -> Vec<BoardPath> {
-    (0..4)
-        .map(|i| match i {
-            0 => (1..(7 - row))
-                .filter_map(|i| (row + i, column).try_into().ok())
-                .collect(),
-            1 => (1..(7 - column))
-                .filter_map(|i| (row, column + i).try_into().ok())
-                .collect(),
-            2 => (1..row)
-                .filter_map(|i| (row - i, column).try_into().ok())
-                .collect(),
-            3 => (1..column)
-                .filter_map(|i| (row, column - i).try_into().ok())
-                .collect(),
-            _ => unreachable!(),
-        })
-        .map(BoardPath)
-        .collect()
-}
-
-fn bishop_movement_pattern(row: usize, column: usize) -> Vec<BoardPath> {
-    vec![(1, 1), (1, -1), (-1, 1), (-1, -1)]
-        .into_iter()
-        .map(|(r, c): (isize, isize)| {
-            (0..7)
-                .filter_map(|i| {
-                    (row as isize + i * r, column as isize + i * c)
-                        .try_into()
-                        .ok()
-                })
-                .collect::<Vec<BoardPosition>>()
-        })
-        .map(BoardPath::from)
-        .collect()
+pub fn get_movement_pattern(piece: &ChessPiece, board: &Board) -> Vec<MovementPath> {
+    board
+        .piece_definition(piece.kind())
+        .movement_paths(piece, &board.dimensions())
 }
 
 pub fn is_en_passant_to_the_right(origin: &BoardPosition, destination: &BoardPosition) -> bool {
@@ -208,18 +159,11 @@ pub fn is_en_passant_to_the_right(origin: &BoardPosition, destination: &BoardPos
     en_passant_to_the_right_pos.contains(destination)
 }
 
-pub fn get_en_passant_to_the_right_pos(position: &BoardPosition) -> BoardPosition {
+/// Get's the position of the pawn that would be captured by an en passant to the right.
+pub fn get_en_passant_to_the_right_pos(position: &BoardPosition) -> Option<BoardPosition> {
     let (row, column) = position.into();
 
-    (row, column + 1).try_into().unwrap_or_else(|_| {
-        panic!(
-            "The en passant to the right position is invalid! `({},{})` -> `({},{})`",
-            row,
-            column,
-            row,
-            column + 1
-        )
-    })
+    (row, column + 1).try_into().ok()
 }
 
 pub fn is_en_passant_to_the_left(origin: &BoardPosition, destination: &BoardPosition) -> bool {
@@ -234,16 +178,9 @@ pub fn is_en_passant_to_the_left(origin: &BoardPosition, destination: &BoardPosi
     positions.contains(destination)
 }
 
-pub fn get_en_passant_to_the_left_pos(position: &BoardPosition) -> BoardPosition {
+/// Get's the position of the pawn that would be captured by an en passant to the left.
+pub fn get_en_passant_to_the_left_pos(position: &BoardPosition) -> Option<BoardPosition> {
     let (row, column) = position.into();
 
-    (row, column - 1).try_into().unwrap_or_else(|_| {
-        panic!(
-            "The en passant to the left position is invalid! `({},{})` -> `({},{})`",
-            row,
-            column,
-            row,
-            column - 1
-        )
-    })
+    (row, column.checked_sub(1)?).try_into().ok()
 }
@@ -11,7 +11,14 @@ fn main() {
         PieceColors::White,
     );
     let destination = "e4".try_into().unwrap();
-    let respone = move_piece(BoardMovement { destination, piece }, &mut board);
+    let respone = move_piece(
+        BoardMovement {
+            destination,
+            piece,
+            promotion: None,
+        },
+        &mut board,
+    );
 
     println!("{}", serde_json::to_string(&respone.unwrap()).unwrap());
 }
@@ -0,0 +1,368 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// A movement of the attacking player that forces the mate the problem asks for, with every
+/// defense against it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatingMove {
+    pub record: MoveRecord,
+    /// Every legal answer of the defending player. It's empty if the movement mates.
+    pub defenses: Vec<MateDefense>,
+}
+
+/// An answer of the defending player, with every movement that keeps forcing mate after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MateDefense {
+    pub record: MoveRecord,
+    /// It's empty if the defense mates, which on a selfmate is what the attacking player wants.
+    pub continuations: Vec<MatingMove>,
+}
+
+/// The solution of a mate in N or a selfmate in N problem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MateSolution {
+    /// The most movements the attacking player can take to mate.
+    pub moves: usize,
+    /// Every first movement that forces mate, with the whole tree of defenses and continuations.
+    pub keys: Vec<MatingMove>,
+}
+
+impl MateSolution {
+    /// Checks if the player that moves next can force mate.
+    pub fn is_sound(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Get's the key movement if it's the only one that forces mate.
+    pub fn unique_key(&self) -> Option<&MatingMove> {
+        match self.keys.as_slice() {
+            [key] => Some(key),
+            _ => None,
+        }
+    }
+}
+
+/// Solves whether the player that moves next can force mate in at most `moves` movements,
+/// whatever the opponent plays. Mates in fewer movements are also solutions.
+/// The time it takes grows very fast with `moves`, so it's meant for composed problems.
+pub fn solve_mate_in(board: &Board, moves: usize) -> MateSolution {
+    MateSolution {
+        moves,
+        keys: get_mating_moves(board, moves),
+    }
+}
+
+/// Solves whether the player that moves next can force its opponent to mate it in at most
+/// `moves` movements, whatever the opponent plays. On a selfmate the opponent tries not to mate,
+/// so the last movement must leave it nothing but mating movements.
+pub fn solve_selfmate_in(board: &Board, moves: usize) -> MateSolution {
+    MateSolution {
+        moves,
+        keys: get_selfmating_moves(board, moves),
+    }
+}
+
+/// The solutions of a helpmate problem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelpmateSolution {
+    /// The most movements each player can make before the mate.
+    pub moves: usize,
+    /// Every line that ends with the player that moved first checkmated, starting with its
+    /// movement.
+    pub lines: Vec<Vec<MoveRecord>>,
+}
+
+impl HelpmateSolution {
+    /// Checks if the problem has a solution.
+    pub fn is_sound(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Checks if the problem has a single solution.
+    pub fn is_unique(&self) -> bool {
+        self.lines.len() == 1
+    }
+}
+
+/// Solves a helpmate in `moves`: both players cooperate so the player that moves next, black on a
+/// usual problem, gets mated by the last movement of its opponent. Shorter mates are also
+/// solutions.
+pub fn solve_helpmate_in(board: &Board, moves: usize) -> HelpmateSolution {
+    let lines = get_helpmate_lines(board, moves)
+        .into_iter()
+        .map(|line| {
+            let mut board = board.clone();
+            line.into_iter()
+                .filter_map(|movement| move_piece(movement, &mut board).ok())
+                .collect()
+        })
+        .collect();
+
+    HelpmateSolution { moves, lines }
+}
+
+/// Get's every movement of the player that moves next that forces mate in at most `moves`
+/// movements, with the answers to every defense.
+fn get_mating_moves(board: &Board, moves: usize) -> Vec<MatingMove> {
+    if moves == 0 {
+        return vec![];
+    }
+
+    get_legal_movements(board, &board.side_to_move)
+        .into_iter()
+        .filter_map(|movement| {
+            let mut moved_board = board.clone();
+            let record = move_piece(movement, &mut moved_board).ok()?;
+            if !is_mated_whatever_it_plays(&moved_board, moves - 1) {
+                return None;
+            }
+
+            let defenses = get_legal_movements(&moved_board, &moved_board.side_to_move)
+                .into_iter()
+                .filter_map(|defense| {
+                    let mut defended_board = moved_board.clone();
+                    let record = move_piece(defense, &mut defended_board).ok()?;
+                    Some(MateDefense {
+                        record,
+                        continuations: get_mating_moves(&defended_board, moves - 1),
+                    })
+                })
+                .collect();
+
+            Some(MatingMove { record, defenses })
+        })
+        .collect()
+}
+
+/// Checks if the player that moves next can mate in at most `moves` movements, whatever the
+/// opponent plays.
+fn can_force_mate(board: &Board, moves: usize) -> bool {
+    moves > 0
+        && get_candidate_movements(board, &board.side_to_move)
+            .into_iter()
+            .any(|movement| {
+                apply_movement(
+                    board,
+                    &movement.piece,
+                    &movement.destination,
+                    movement.promotion,
+                )
+                .map_or(false, |applied_movement| {
+                    is_mated_whatever_it_plays(&applied_movement.board, moves - 1)
+                })
+            })
+}
+
+/// Checks if the player that moves next is checkmated, or will be in at most `moves` movements
+/// of the opponent whatever it plays.
+fn is_mated_whatever_it_plays(board: &Board, moves: usize) -> bool {
+    let color = &board.side_to_move;
+    let mut has_legal_movements = false;
+    for movement in get_candidate_movements(board, color) {
+        let Ok(applied_movement) = apply_movement(
+            board,
+            &movement.piece,
+            &movement.destination,
+            movement.promotion,
+        ) else {
+            continue;
+        };
+
+        has_legal_movements = true;
+        if !can_force_mate(&applied_movement.board, moves) {
+            return false;
+        }
+    }
+
+    // Without legal movements it's either checkmate or stalemate.
+    has_legal_movements || board.is_in_check(color)
+}
+
+/// Get's every movement of the player that moves next that forces its opponent to mate it in at
+/// most `moves` movements, with the answers to every defense.
+fn get_selfmating_moves(board: &Board, moves: usize) -> Vec<MatingMove> {
+    if moves == 0 {
+        return vec![];
+    }
+
+    get_legal_movements(board, &board.side_to_move)
+        .into_iter()
+        .filter_map(|movement| {
+            let mut moved_board = board.clone();
+            let record = move_piece(movement, &mut moved_board).ok()?;
+            if !is_forced_to_mate(&moved_board, moves - 1) {
+                return None;
+            }
+
+            let defenses = get_legal_movements(&moved_board, &moved_board.side_to_move)
+                .into_iter()
+                .filter_map(|defense| {
+                    let mut defended_board = moved_board.clone();
+                    let record = move_piece(defense, &mut defended_board).ok()?;
+                    Some(MateDefense {
+                        record,
+                        continuations: get_selfmating_moves(&defended_board, moves - 1),
+                    })
+                })
+                .collect();
+
+            Some(MatingMove { record, defenses })
+        })
+        .collect()
+}
+
+/// Checks if the player that moves next can force its opponent to mate it in at most `moves`
+/// movements.
+fn can_force_selfmate(board: &Board, moves: usize) -> bool {
+    moves > 0
+        && get_candidate_movements(board, &board.side_to_move)
+            .into_iter()
+            .any(|movement| {
+                apply_movement(
+                    board,
+                    &movement.piece,
+                    &movement.destination,
+                    movement.promotion,
+                )
+                .map_or(false, |applied_movement| {
+                    is_forced_to_mate(&applied_movement.board, moves - 1)
+                })
+            })
+}
+
+/// Checks if every movement of the player that moves next mates its opponent, or lets the
+/// opponent force it to mate in at most `moves` more movements.
+fn is_forced_to_mate(board: &Board, moves: usize) -> bool {
+    let opponent = board.side_to_move.opponent();
+    let mut has_legal_movements = false;
+    for movement in get_candidate_movements(board, &board.side_to_move) {
+        let Ok(applied_movement) = apply_movement(
+            board,
+            &movement.piece,
+            &movement.destination,
+            movement.promotion,
+        ) else {
+            continue;
+        };
+
+        has_legal_movements = true;
+        let mates = applied_movement.board.is_in_check(&opponent)
+            && applied_movement.board.is_checkmate(&opponent);
+        if !mates && !can_force_selfmate(&applied_movement.board, moves) {
+            return false;
+        }
+    }
+
+    has_legal_movements
+}
+
+/// Get's every line of at most `moves` movements of each player where the player that moves next
+/// gets mated.
+fn get_helpmate_lines(board: &Board, moves: usize) -> Vec<Vec<BoardMovement>> {
+    let mut lines = vec![];
+    if moves == 0 {
+        return lines;
+    }
+
+    let mated_color = &board.side_to_move;
+    for movement in get_candidate_movements(board, mated_color) {
+        let Ok(applied_movement) = apply_movement(
+            board,
+            &movement.piece,
+            &movement.destination,
+            movement.promotion,
+        ) else {
+            continue;
+        };
+
+        let helped_board = &applied_movement.board;
+        for mating_movement in get_candidate_movements(helped_board, &helped_board.side_to_move) {
+            let Ok(mated_movement) = apply_movement(
+                helped_board,
+                &mating_movement.piece,
+                &mating_movement.destination,
+                mating_movement.promotion,
+            ) else {
+                continue;
+            };
+
+            let line = vec![movement.clone(), mating_movement];
+            let mated_board = &mated_movement.board;
+            if mated_board.is_in_check(mated_color) && mated_board.is_checkmate(mated_color) {
+                lines.push(line);
+                continue;
+            }
+
+            for continuation in get_helpmate_lines(mated_board, moves - 1) {
+                lines.push([line.clone(), continuation].concat());
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_morphys_mate_in_two() {
+        let board = Fen::try_from("kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1")
+            .unwrap()
+            .board;
+
+        assert!(!solve_mate_in(&board, 1).is_sound());
+
+        let solution = solve_mate_in(&board, 2);
+        let key = solution.unique_key().unwrap();
+        assert_eq!(key.record.to_uci(), "a1a6");
+
+        let mut defenses: Vec<String> = key
+            .defenses
+            .iter()
+            .map(|defense| defense.record.to_uci())
+            .collect();
+        defenses.sort();
+        assert_eq!(
+            defenses,
+            ["b7a6", "b8c7", "b8d6", "b8e5", "b8f4", "b8g3", "b8h2"]
+        );
+        assert!(key
+            .defenses
+            .iter()
+            .all(|defense| !defense.continuations.is_empty()));
+    }
+
+    #[test]
+    fn finds_every_fools_mate_as_a_helpmate_in_two() {
+        let solution = solve_helpmate_in(&get_starting_board(), 2);
+
+        let mut lines: Vec<String> = solution
+            .lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|record| record.san.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                "f3 e5 g4 Qh4#",
+                "f3 e6 g4 Qh4#",
+                "f4 e5 g4 Qh4#",
+                "f4 e6 g4 Qh4#",
+                "g4 e5 f3 Qh4#",
+                "g4 e5 f4 Qh4#",
+                "g4 e6 f3 Qh4#",
+                "g4 e6 f4 Qh4#",
+            ]
+        );
+        assert!(!solution.is_unique());
+    }
+}
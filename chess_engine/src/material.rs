@@ -0,0 +1,52 @@
+use super::*;
+
+impl Board {
+    /// Checks if neither player can checkmate anymore, whatever movements are played.
+    /// That happens when there are only kings, kings and a single knight or bishop, or kings and
+    /// bishops that are all on cells of the same color.
+    pub fn is_insufficient_material(&self) -> bool {
+        let pieces: Vec<&ChessPiece> = self
+            .white_pieces
+            .iter()
+            .chain(self.black_pieces.iter())
+            .filter(|piece| piece.kind() != &PieceTypes::King)
+            .collect();
+
+        match pieces.as_slice() {
+            [] => true,
+            [piece] => matches!(piece.kind(), PieceTypes::Knight | PieceTypes::Bishop),
+            _ => are_bishops_on_same_cell_color(&pieces),
+        }
+    }
+
+    /// Checks if the player of the given color can't checkmate with his pieces.
+    /// A lone king, or a king with a single knight or bishop, are never considered enough.
+    pub fn has_insufficient_material(&self, color: &PieceColors) -> bool {
+        let pieces: Vec<&ChessPiece> = self
+            .get_pieces_from(color)
+            .iter()
+            .filter(|piece| piece.kind() != &PieceTypes::King)
+            .collect();
+
+        match pieces.as_slice() {
+            [] => true,
+            [piece] => matches!(piece.kind(), PieceTypes::Knight | PieceTypes::Bishop),
+            _ => false,
+        }
+    }
+}
+
+/// Checks if all the pieces are bishops standing on cells of the same color.
+fn are_bishops_on_same_cell_color(pieces: &[&ChessPiece]) -> bool {
+    let cell_color = |piece: &ChessPiece| {
+        let (row, column) = piece.position();
+        (row + column) % 2
+    };
+
+    pieces
+        .iter()
+        .all(|piece| piece.kind() == &PieceTypes::Bishop)
+        && pieces
+            .iter()
+            .all(|piece| cell_color(piece) == cell_color(pieces[0]))
+}
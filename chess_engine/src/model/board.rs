@@ -1,10 +1,11 @@
 use crate::{
-    get_en_passant_to_the_left_pos, get_en_passant_to_the_right_pos, get_movement_pattern,
-    get_valid_movements_positions, is_en_passant_to_the_left, is_en_passant_to_the_right,
-    is_pawn_on_starting_position, BoardPath, ChessBoardDirections, ChessPiece, PieceTypes,
+    castling_files, get_en_passant_to_the_left_pos, get_en_passant_to_the_right_pos,
+    get_movement_pattern, get_valid_movements_positions, is_en_passant_to_the_left, BoardPath,
+    BoardProblem, ChessBoardDirections, ChessPiece, FairyPiece, MovementError, MovementKind,
+    MovementPath, PieceTypes,
 };
 
-use super::{BoardPosition, ChessCell, PieceColors};
+use super::{BoardDimensions, BoardPosition, ChessCell, PieceColors};
 
 use serde::{Deserialize, Serialize};
 
@@ -34,10 +35,17 @@ pub struct Board {
     pub white_castling: Option<CastlingState>,
     pub black_castling: Option<CastlingState>,
 
+    pub side_to_move: PieceColors,
+
     pub cells: Vec<Vec<ChessCell>>,
+
+    /// The pieces not on standard chess that can be played on this board.
+    #[serde(default)]
+    pub fairy_pieces: Vec<FairyPiece>,
 }
 
 impl Board {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cells: Vec<Vec<ChessCell>>,
         black_pieces: Vec<ChessPiece>,
@@ -59,9 +67,25 @@ impl Board {
             en_passant_position,
             white_castling,
             black_castling,
+            side_to_move: PieceColors::White,
+            fairy_pieces: vec![],
+        }
+    }
+
+    /// Get's the size of this board.
+    pub fn dimensions(&self) -> BoardDimensions {
+        BoardDimensions {
+            files: self.cells.first().map_or(0, Vec::len),
+            ranks: self.cells.len(),
         }
     }
 
+    /// Removes the piece on the given position from the board, if there's one.
+    /// Removing a rook from its starting corner also removes the castling rights with it.
+    pub fn remove_piece(&mut self, position: &BoardPosition) -> Option<ChessPiece> {
+        self.take_piece(position).ok().flatten()
+    }
+
     pub fn is_in_check(&self, owner: &PieceColors) -> bool {
         if let Some(CheckedState { color_in_check, .. }) = &self.check_state {
             color_in_check == owner
@@ -89,200 +113,168 @@ impl Board {
             })
     }
 
-    fn get_pieces_from(&self, color: &PieceColors) -> &Vec<ChessPiece> {
+    pub(crate) fn get_pieces_from(&self, color: &PieceColors) -> &Vec<ChessPiece> {
         match color {
             PieceColors::Black => &self.black_pieces,
             PieceColors::White => &self.white_pieces,
         }
     }
 
-    /// Moves a piece in the board itself. This method expects everything passed to it to be
-    /// correct, so it doesn't checks for collisions nor movement patterns.
+    /// Moves a piece in the board itself. This method expects the movement to follow the piece
+    /// movement pattern, so it doesn't checks for collisions nor checks.
+    /// The pawn becomes the promotion piece if one is given.
+    /// Returns the captured piece, including pawns captured en passant.
+    /// Fails if the representations of the board don't agree with each other, in which case the
+    /// board may be left half moved, so the movement should be made on a copy of the board.
     pub(crate) fn move_piece(
         &mut self,
-        mut piece: ChessPiece,
+        piece: ChessPiece,
         destination: &BoardPosition,
         is_castling: Option<ChessBoardDirections>,
-    ) {
-        let is_starting_position = is_pawn_on_starting_position(&piece);
-        let (row, column) = piece.position();
-        let position: BoardPosition = (row, column).try_into().unwrap();
-        piece.update_position(destination.clone());
-
-        let Board {
-            black_pieces,
-            white_pieces,
-            cells,
-            white_king_position,
-            black_king_position,
-            en_passant_position,
-            white_castling,
-            black_castling,
-            ..
-        } = self;
-
-        match (piece.kind(), piece.color()) {
-            (PieceTypes::King, PieceColors::Black) => {
-                match is_castling {
-                    Some(ChessBoardDirections::Left) => {
-                        let rook_position: BoardPosition = "d8".try_into().unwrap();
-                        let left_rook = black_pieces
-                            .iter_mut()
-                            .find(|p| {
-                                p.kind() == &PieceTypes::Rook
-                                    && p.board_position() == &rook_position
-                            })
-                            .expect("Black left rook should be in black pieces array!");
-                        move_rook_in_position(left_rook, rook_position, cells);
-                    }
-                    Some(ChessBoardDirections::Right) => {
-                        let rook_position: BoardPosition = "f8".try_into().unwrap();
-                        let right_rook = black_pieces
-                            .iter_mut()
-                            .find(|p| {
-                                p.kind() == &PieceTypes::Rook
-                                    && p.board_position() == &rook_position
-                            })
-                            .expect("Black right rook should be in black pieces array!");
-                        move_rook_in_position(right_rook, rook_position, cells);
-                    }
-                    _ => unreachable!(),
-                };
-                *black_king_position = destination.clone();
-                *black_castling = None;
-            }
-            (PieceTypes::King, PieceColors::White) => {
-                match is_castling {
-                    Some(ChessBoardDirections::Left) => {
-                        let rook_position: BoardPosition = "d1".try_into().unwrap();
-                        let left_rook = black_pieces
-                            .iter_mut()
-                            .find(|p| {
-                                p.kind() == &PieceTypes::Rook
-                                    && p.board_position() == &rook_position
-                            })
-                            .expect("White left rook should be in black pieces array!");
-                        move_rook_in_position(left_rook, rook_position, cells);
-                    }
-                    Some(ChessBoardDirections::Right) => {
-                        let rook_position: BoardPosition = "f1".try_into().unwrap();
-                        let right_rook = black_pieces
-                            .iter_mut()
-                            .find(|p| {
-                                p.kind() == &PieceTypes::Rook
-                                    && p.board_position() == &rook_position
-                            })
-                            .expect("White right rook should be in black pieces array!");
-                        move_rook_in_position(right_rook, rook_position, cells);
-                    }
-                    _ => unreachable!(),
-                };
-                *white_king_position = destination.clone();
-                *white_castling = None;
-            }
-            _ => {}
+        promotion: Option<PieceTypes>,
+    ) -> Result<Option<ChessPiece>, MovementError> {
+        let origin = piece.board_position().clone();
+        if self.get_piece(&origin).as_ref() != Some(&piece) {
+            return Err(MovementError::PieceNotOnBoard);
         }
 
-        let (dest_row, dest_column) = destination.into();
+        let dimensions = self.dimensions();
         let piece_color = piece.color().clone();
-        let piece_kind = *piece.kind();
+        let (row, column) = piece.position();
+        let (dest_row, dest_column): (usize, usize) = destination.into();
+
+        let mut piece = self
+            .take_piece(&origin)?
+            .ok_or_else(|| BoardProblem::PieceMissingFromCells(origin.clone()))?;
+
+        if let Some(direction) = is_castling {
+            let rook_origin_file = match direction {
+                ChessBoardDirections::Right => dimensions.files.saturating_sub(1),
+                _ => 0,
+            };
+            let (_, rook_destination_file) = castling_files(&direction, &dimensions);
+            let rook_origin = board_position((row, rook_origin_file))?;
+
+            let mut rook = self
+                .take_piece(&rook_origin)?
+                .filter(|p| p.kind() == &PieceTypes::Rook && p.color() == &piece_color)
+                .ok_or_else(|| {
+                    BoardProblem::CastlingWithoutRook(piece_color.clone(), rook_origin.clone())
+                })?;
+            rook.update_position(board_position((row, rook_destination_file))?);
+            self.put_piece(rook)?;
+        }
 
-        let cell = cells
-            .get_mut(dest_row)
-            .unwrap()
-            .get_mut(dest_column)
-            .unwrap();
+        let kind = *piece.kind();
+        piece.update_position(destination.clone());
+        if let Some(promotion) = promotion {
+            piece = ChessPiece::new(promotion, destination.clone(), piece_color.clone());
+        }
+        let mut captured_piece = self.put_piece(piece)?;
+
+        // A pawn moving diagonally into an empty cell captures en passant.
+        if kind == PieceTypes::Pawn && column != dest_column && captured_piece.is_none() {
+            let en_passant_position = if is_en_passant_to_the_left(&origin, destination) {
+                get_en_passant_to_the_left_pos(&origin)
+            } else {
+                get_en_passant_to_the_right_pos(&origin)
+            };
+            match en_passant_position {
+                Some(position) if self.en_passant_position.as_ref() == Some(&position) => {
+                    captured_piece = self.take_piece(&position)?;
+                }
+                _ => return Err(MovementError::DestinationDoesntFollowMovementPattern),
+            }
+        }
 
-        *cell = match cell.piece() {
-            Some(piece_in_cell) => match piece_in_cell.color() {
+        if kind == PieceTypes::King {
+            match piece_color {
                 PieceColors::Black => {
-                    let index = black_pieces
-                        .iter()
-                        .position(|p| p == &piece_in_cell)
-                        .unwrap_or_else(|| {
-                            panic!("There's no black piece that matches {:?}", &piece_in_cell)
-                        });
-                    black_pieces.remove(index);
-
-                    ChessCell::some(piece)
+                    self.black_king_position = destination.clone();
+                    self.black_castling = None;
                 }
                 PieceColors::White => {
-                    let index = white_pieces
-                        .iter()
-                        .position(|p| p == &piece_in_cell)
-                        .unwrap_or_else(|| {
-                            panic!("There's no white piece that matches {:?}", &piece_in_cell)
-                        });
-                    white_pieces.remove(index);
-
-                    ChessCell::some(piece)
+                    self.white_king_position = destination.clone();
+                    self.white_castling = None;
                 }
-            },
-            None => ChessCell::some(piece),
+            }
+        }
+
+        // Only a pawn that just moved two cells can be captured en passant.
+        self.en_passant_position = if kind == PieceTypes::Pawn && row.abs_diff(dest_row) == 2 {
+            Some(destination.clone())
+        } else {
+            None
         };
 
-        if let PieceTypes::Pawn = piece_kind {
-            let destination_is_two_blocks_over = row + 2 == dest_row || column + 2 == dest_column;
-
-            // Pawn did an en passant.
-            if en_passant_position.is_some() {
-                let piece_to_remove_position = match (
-                    is_en_passant_to_the_left(&position, destination),
-                    is_en_passant_to_the_right(&position, destination),
-                ) {
-                    (true, _) => Some(get_en_passant_to_the_left_pos(&position)),
-                    (_, true) => Some(get_en_passant_to_the_right_pos(&position)),
-                    _ => None,
-                };
-
-                if let Some(pos) = piece_to_remove_position {
-                    let (pos_row, pos_column) = pos.into();
-                    let cell = cells.get_mut(pos_row).unwrap().get_mut(pos_column).unwrap();
-                    *cell = match cell.piece() {
-                        Some(eaten_piece) => {
-                            match eaten_piece.color() {
-                                PieceColors::Black => {
-                                    let pos = black_pieces
-                                        .iter()
-                                        .position(|p| p == &eaten_piece)
-                                        .unwrap_or_else(|| {
-                                            panic!(
-                                                "There's no black piece that matches {:?}",
-                                                &eaten_piece
-                                            )
-                                        });
-                                    black_pieces.remove(pos);
-                                }
-                                PieceColors::White => {
-                                    let pos = white_pieces
-                                        .iter()
-                                        .position(|p| p == &eaten_piece)
-                                        .unwrap_or_else(|| {
-                                            panic!(
-                                                "There's no white piece that matches {:?}",
-                                                &eaten_piece
-                                            )
-                                        });
-
-                                    white_pieces.remove(pos);
-                                }
-                            };
-                            ChessCell::none()
-                        }
-                        None => unreachable!("It should not be possible to en passant here!"),
-                    }
-                }
-            }
-            // Pawn started moving with two spaces.
-            else if is_starting_position && destination_is_two_blocks_over {
-                *en_passant_position = Some(destination.clone());
+        self.update_check_state(&piece_color);
+        self.side_to_move = piece_color.opponent();
+        Ok(captured_piece)
+    }
+
+    /// Takes the piece on the given position out of the cells and out of the pieces of its color.
+    /// Taking a rook from its starting corner also removes the castling rights with it.
+    /// Fails without touching the board if the piece is not on the pieces of its color.
+    fn take_piece(&mut self, position: &BoardPosition) -> Result<Option<ChessPiece>, BoardProblem> {
+        let Some(piece) = self.get_piece(position) else {
+            return Ok(None);
+        };
+        let (row, column): (usize, usize) = position.into();
+        let dimensions = self.dimensions();
+        let (white_back_rank, black_back_rank) = dimensions.back_ranks();
+
+        let (pieces, castling, back_rank) = match piece.color() {
+            PieceColors::Black => (
+                &mut self.black_pieces,
+                &mut self.black_castling,
+                black_back_rank,
+            ),
+            PieceColors::White => (
+                &mut self.white_pieces,
+                &mut self.white_castling,
+                white_back_rank,
+            ),
+        };
+        let index = pieces
+            .iter()
+            .position(|p| p == &piece)
+            .ok_or_else(|| BoardProblem::PieceMissingFromPieces(position.clone()))?;
+        pieces.remove(index);
+
+        if let (PieceTypes::Rook, Some(castling_state)) = (piece.kind(), castling.as_mut()) {
+            if row == back_rank && column == 0 {
+                castling_state.can_use_left_rook = false;
+            } else if row == back_rank && column + 1 == dimensions.files {
+                castling_state.can_use_right_rook = false;
             }
-        // Any other piece moved or it was not an en passant move by a pawn.
-        } else {
-            *en_passant_position = None;
         }
 
-        self.update_check_state(&piece_color);
+        if let Some(cell) = self.cells.get_mut(row).and_then(|r| r.get_mut(column)) {
+            *cell = ChessCell::none();
+        }
+
+        Ok(Some(piece))
+    }
+
+    /// Puts the piece on its position, both on the cells and on the pieces of its color.
+    /// Returns the piece that was on that position before, which is taken out of the board.
+    fn put_piece(&mut self, piece: ChessPiece) -> Result<Option<ChessPiece>, BoardProblem> {
+        let position = piece.board_position().clone();
+        if !self.dimensions().contains(&position) {
+            return Err(BoardProblem::PositionOutsideBoard(position));
+        }
+
+        let captured_piece = self.take_piece(&position)?;
+        let (row, column) = piece.position();
+        if let Some(cell) = self.cells.get_mut(row).and_then(|r| r.get_mut(column)) {
+            *cell = ChessCell::some(piece.clone());
+        }
+        match piece.color() {
+            PieceColors::Black => self.black_pieces.push(piece),
+            PieceColors::White => self.white_pieces.push(piece),
+        }
+
+        Ok(captured_piece)
     }
 
     /// Get's the king position of the given color.
@@ -296,80 +288,79 @@ impl Board {
     /// Checks if a king of the given color in the given position would be in check in the given board.
     /// Returns true if the positions would be in check.
     pub fn position_in_check(&self, position: &BoardPosition, color: &PieceColors) -> bool {
-        let opponent_pieces = self.get_pieces_from(&color.opponent());
-        opponent_pieces
-            .iter()
-            .flat_map(|piece| self.get_movement_paths(piece))
-            .flat_map(|path| path.0)
-            .any(|pos| pos == *position)
+        self.is_attacked(position, &color.opponent())
     }
 
     /// Get's all the valid movement paths the piece can take, considering collisions with other pieces.
     /// This method doesn't considers the check state.
     pub(crate) fn get_movement_paths(&self, piece: &ChessPiece) -> Vec<BoardPath> {
-        let mut pattern = get_movement_pattern(piece);
-        let pawn_capture_pattern: Option<Vec<BoardPath>> = self.get_capture_pattern(piece);
+        let pattern = get_movement_pattern(piece, self);
+        let mut paths: Vec<BoardPath> = self.colission_detection(pattern, piece);
 
-        if let (PieceTypes::Pawn, Some(mut paths)) = (piece.kind(), pawn_capture_pattern) {
-            pattern.append(&mut paths);
+        if let Some(path) = self.get_en_passant_path(piece) {
+            paths.push(path);
         }
 
-        let paths: Vec<BoardPath> = self.colission_detection(pattern, piece);
         paths
     }
 
-    /// Get's the movement pattern of this piece if it can capture a piece.
-    /// The only piece that actually needs this method is the pawn, because it can capture diagonally.
-    pub(crate) fn get_capture_pattern(&self, piece: &ChessPiece) -> Option<Vec<BoardPath>> {
-        let oponent_color = piece.color().opponent();
-        if let PieceTypes::Pawn = piece.kind() {
-            let (row, column) = piece.position();
-            let positions_to_check = match piece.color() {
-                PieceColors::Black => vec![(-1, -1), (-1, 1)],
-                PieceColors::White => vec![(1, -1), (1, 1)],
-            };
+    /// Get's all the positions the piece could move to without considering checks.
+    pub(crate) fn get_pseudo_legal_positions(&self, piece: &ChessPiece) -> Vec<BoardPosition> {
+        self.get_movement_paths(piece)
+            .into_iter()
+            .flat_map(|path| path.0)
+            .collect()
+    }
 
-            let mut paths: Vec<BoardPath> = positions_to_check
-                .into_iter()
-                .filter_map(|(r, c): (isize, isize)| {
-                    self.get_piece(&((row as isize + r, column as isize + c).try_into().ok()?))
-                })
-                .filter(|chess_piece| *chess_piece.color() == oponent_color)
-                .filter_map(|chess_piece| chess_piece.position().try_into().ok())
-                .map(|p: BoardPosition| vec![p])
-                .map(BoardPath::from)
-                .collect();
+    /// Get's all the positions the player of the given color can see.
+    /// A player sees the cells his pieces are on and every cell they could move to.
+    pub fn visible_positions(&self, color: &PieceColors) -> Vec<BoardPosition> {
+        let pieces = self.get_pieces_from(color);
+        let mut positions: Vec<BoardPosition> = pieces
+            .iter()
+            .map(|piece| piece.board_position().clone())
+            .collect();
 
-            if let Some(board_position) = &self.en_passant_position {
-                let (other_row, other_column) = board_position.into();
-
-                let same_row = row == other_row;
-                let on_right = column as isize + 1 == other_column as isize;
-                let on_left = column as isize - 1 == other_column as isize;
-
-                let position_to_check = match (same_row, on_right, on_left) {
-                    (true, true, _) => match piece.color() {
-                        PieceColors::Black => Some((-1, 1)),
-                        PieceColors::White => Some((1, 1)),
-                    },
-                    (true, _, true) => match piece.color() {
-                        PieceColors::Black => Some((-1, -1)),
-                        PieceColors::White => Some((1, -1)),
-                    },
-                    _ => None,
-                };
-
-                if let Some((r, c)) = position_to_check {
-                    let board_pos = (row as isize + r, column as isize + c).try_into().ok()?;
-                    let cell = self.get_cell(&board_pos);
-
-                    if let None | Some(ChessCell(None)) = cell {
-                        let path = BoardPath(vec![board_pos]);
-                        paths.push(path);
-                    }
-                }
+        for position in pieces
+            .iter()
+            .flat_map(|piece| self.get_pseudo_legal_positions(piece))
+        {
+            if !positions.contains(&position) {
+                positions.push(position);
             }
-            Some(paths)
+        }
+
+        positions
+    }
+
+    /// Get's the path a pawn would take to capture en passant, if it can.
+    pub(crate) fn get_en_passant_path(&self, piece: &ChessPiece) -> Option<BoardPath> {
+        if piece.kind() != &PieceTypes::Pawn {
+            return None;
+        }
+
+        let (row, column) = piece.position();
+        let (other_row, other_column) = self.en_passant_position.as_ref()?.into();
+
+        let same_row = row == other_row;
+        let on_right = column as isize + 1 == other_column as isize;
+        let on_left = column as isize - 1 == other_column as isize;
+
+        let (r, c) = match (same_row, on_right, on_left) {
+            (true, true, _) => match piece.color() {
+                PieceColors::Black => (-1, 1),
+                PieceColors::White => (1, 1),
+            },
+            (true, _, true) => match piece.color() {
+                PieceColors::Black => (-1, -1),
+                PieceColors::White => (1, -1),
+            },
+            _ => return None,
+        };
+
+        let board_pos = (row as isize + r, column as isize + c).try_into().ok()?;
+        if let None | Some(ChessCell(None)) = self.get_cell(&board_pos) {
+            Some(BoardPath(vec![board_pos]))
         } else {
             None
         }
@@ -377,15 +368,16 @@ impl Board {
 
     /// Checks all the paths and checks for collisions with another pieces.
     /// The inputs are the piece to and the pattern of the given piece.
-    /// Returns the new paths now taking into account collision with other pieces.
+    /// Returns the new paths now taking into account collision with other pieces and the kind of
+    /// movement allowed on each path.
     pub(crate) fn colission_detection(
         &self,
-        pattern: Vec<BoardPath>,
+        pattern: Vec<MovementPath>,
         piece: &ChessPiece,
     ) -> Vec<BoardPath> {
         pattern
             .into_iter()
-            .map(|path| {
+            .map(|MovementPath { path, kind }| {
                 let path: Vec<BoardPosition> = path.0;
                 path.into_iter()
                     .scan(false, |found_piece, position| {
@@ -402,12 +394,24 @@ impl Board {
 
                         *found_piece = piece_option.is_some();
 
-                        if piece_option.is_none() || has_enemy_piece {
-                            Some(position)
+                        let can_move_here = match kind {
+                            MovementKind::MoveAndCapture => {
+                                piece_option.is_none() || has_enemy_piece
+                            }
+                            MovementKind::MoveOnly => piece_option.is_none(),
+                            MovementKind::CaptureOnly => has_enemy_piece,
+                        };
+
+                        if can_move_here {
+                            Some(Some(position))
+                        } else if piece_option.is_none() {
+                            // Capture only paths can keep looking for a piece to capture.
+                            Some(None)
                         } else {
                             None
                         }
                     })
+                    .flatten()
                     .collect::<Vec<BoardPosition>>()
             })
             .map(BoardPath::from)
@@ -416,16 +420,7 @@ impl Board {
 
     /// Checks if the player of the given color is in stalemate.
     pub fn is_stalemate(&self, color: &PieceColors) -> bool {
-        match &self.check_state {
-            Some(CheckedState { color_in_check, .. }) => {
-                if color_in_check == color {
-                    false
-                } else {
-                    !self.can_any_piece_move(color)
-                }
-            }
-            None => !self.can_any_piece_move(color),
-        }
+        !self.is_in_check(color) && !self.can_any_piece_move(color)
     }
 
     fn can_any_piece_move(&self, color: &PieceColors) -> bool {
@@ -436,7 +431,7 @@ impl Board {
             .any(|mp| !mp.is_empty())
     }
 
-    fn update_check_state(&mut self, color_to_check_first: &PieceColors) {
+    pub(crate) fn update_check_state(&mut self, color_to_check_first: &PieceColors) {
         let opponent_color = color_to_check_first.opponent();
         let colors = vec![color_to_check_first, &opponent_color];
 
@@ -467,39 +462,7 @@ impl Board {
 
     /// Checks if the king of the given color is in checkmate.
     pub fn is_checkmate(&self, king_color: &PieceColors) -> bool {
-        let king_position = self.get_king_position(king_color);
-        let king = self
-            .get_piece(&king_position)
-            .expect("The king position should always be synchronized.");
-
-        match &self.check_state {
-            Some(CheckedState {
-                color_in_check,
-                check_paths,
-            }) => {
-                if color_in_check != king_color {
-                    false
-                } else {
-                    let can_king_move = get_valid_movements_positions(&king, self).is_empty();
-                    if can_king_move {
-                        return false;
-                    }
-                    if !check_paths.is_empty() {
-                        return true;
-                    }
-
-                    let check_positions: Vec<BoardPosition> =
-                        check_paths.iter().flat_map(|pos| pos.clone().0).collect();
-
-                    self.get_pieces_from(king_color)
-                        .iter()
-                        .flat_map(|p| self.get_movement_paths(p))
-                        .flat_map(|path| path.0)
-                        .any(|pos| check_positions.contains(&pos))
-                }
-            }
-            None => false,
-        }
+        self.is_in_check(king_color) && !self.can_any_piece_move(king_color)
     }
 
     /// Get's the castling state for the specified color
@@ -511,23 +474,9 @@ impl Board {
     }
 }
 
-/// Moves the piece that must be a rook, to the specified position.
-/// Makes all the modifications to the cells matrix too.
-fn move_rook_in_position(rook: &mut ChessPiece, to: BoardPosition, cells: &mut [Vec<ChessCell>]) {
-    let (rook_row, rook_column) = rook.position();
-    let rook_cell = cells
-        .get_mut(rook_row)
-        .unwrap()
-        .get_mut(rook_column)
-        .unwrap();
-    *rook_cell = ChessCell::none();
-
-    rook.update_position(to);
-    let (rook_row, rook_column) = rook.position();
-    let rook_cell = cells
-        .get_mut(rook_row)
-        .unwrap()
-        .get_mut(rook_column)
-        .unwrap();
-    *rook_cell = ChessCell::some(rook.clone());
+/// Get's the position on the given row and column, failing if it can't be on a board.
+fn board_position(position: (usize, usize)) -> Result<BoardPosition, BoardProblem> {
+    position
+        .try_into()
+        .map_err(|_| BoardProblem::InvalidDimensions)
 }
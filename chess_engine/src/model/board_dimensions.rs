@@ -0,0 +1,50 @@
+use super::{ArrayIndex, BoardPosition};
+use serde::{Deserialize, Serialize};
+
+/// The maximum amount of files (columns) a board can have.
+pub const MAX_FILES: usize = 16;
+/// The maximum amount of ranks (rows) a board can have.
+pub const MAX_RANKS: usize = 16;
+
+/// The size of a board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardDimensions {
+    pub files: usize,
+    pub ranks: usize,
+}
+
+impl BoardDimensions {
+    /// The classic 8x8 board.
+    pub const STANDARD: BoardDimensions = BoardDimensions { files: 8, ranks: 8 };
+    /// The 10x8 board used by Capablanca chess.
+    pub const CAPABLANCA: BoardDimensions = BoardDimensions {
+        files: 10,
+        ranks: 8,
+    };
+    /// The 5x5 board used by Gardner minichess.
+    pub const GARDNER: BoardDimensions = BoardDimensions { files: 5, ranks: 5 };
+    /// The 6x6 board used by Los Alamos chess.
+    pub const LOS_ALAMOS: BoardDimensions = BoardDimensions { files: 6, ranks: 6 };
+
+    /// Checks if the given position is inside a board of this size.
+    pub fn contains(&self, position: &BoardPosition) -> bool {
+        position.row.to_index() < self.ranks && position.column.to_index() < self.files
+    }
+
+    /// Get's the rank index of the back rank of each player.
+    /// Returns a tuple of the form `(white, black)`.
+    pub fn back_ranks(&self) -> (usize, usize) {
+        (0, self.ranks.saturating_sub(1))
+    }
+
+    /// Checks if pawns can move two cells on their first move.
+    /// Small boards like the Gardner and Los Alamos ones don't allow it.
+    pub fn allows_pawn_double_step(&self) -> bool {
+        self.ranks >= 8
+    }
+
+    /// Get's the file index the kings start on when castling is allowed.
+    pub fn king_file(&self) -> usize {
+        self.files / 2
+    }
+}
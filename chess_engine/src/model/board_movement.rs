@@ -1,9 +1,12 @@
-use crate::{BoardPosition, ChessPiece};
+use crate::{BoardPosition, ChessPiece, PieceTypes};
 use serde::{Deserialize, Serialize};
 
 /// Groups all the necessary information to move a piece.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BoardMovement {
     pub piece: ChessPiece,
     pub destination: BoardPosition,
+    /// The piece a pawn becomes when reaching the last rank. Pawns become queens if it's not set.
+    #[serde(default)]
+    pub promotion: Option<PieceTypes>,
 }
@@ -1,6 +1,6 @@
 use crate::{ArrayIndex, FileInstancingErrors, RankInstancingErrors};
 
-pub use super::{ChessFile, ChessRank, FromMatrixPositionError, MatrixPosition};
+pub use super::{ChessFile, ChessRank};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -25,25 +25,28 @@ pub enum BoardPositionFromStrErrors {
     InvalidRowFormat(RankInstancingErrors),
     #[error("The rank (`{0}`) must be a number.")]
     RankMustBeANumber(String),
-    #[error("The value (`{0}`) must be of length 2.")]
-    ValueTooLarge(String),
+    #[error("The value (`{0}`) must be a file followed by a rank.")]
+    InvalidLength(String),
 }
 
 impl TryFrom<&str> for BoardPosition {
     type Error = BoardPositionFromStrErrors;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.chars().count() == 2 {
-            let column = ChessFile::try_from(value.chars().next().unwrap())
-                .map_err(BoardPositionFromStrErrors::InvalidColumnFormat)?;
-            let row: usize = value.get(1..=1).unwrap().parse().map_err(|_| {
-                BoardPositionFromStrErrors::RankMustBeANumber(value.get(1..=1).unwrap().to_string())
-            })?;
-            let row =
-                ChessRank::try_from(row).map_err(BoardPositionFromStrErrors::InvalidRowFormat)?;
-            Ok(BoardPosition { column, row })
-        } else {
-            Err(BoardPositionFromStrErrors::ValueTooLarge(value.to_string()))
+        let mut chars = value.chars();
+        let (Some(column), rank) = (chars.next(), chars.as_str()) else {
+            return Err(BoardPositionFromStrErrors::InvalidLength(value.to_string()));
+        };
+        if rank.is_empty() || rank.len() > 2 {
+            return Err(BoardPositionFromStrErrors::InvalidLength(value.to_string()));
         }
+
+        let column =
+            ChessFile::try_from(column).map_err(BoardPositionFromStrErrors::InvalidColumnFormat)?;
+        let row: usize = rank
+            .parse()
+            .map_err(|_| BoardPositionFromStrErrors::RankMustBeANumber(rank.to_string()))?;
+        let row = ChessRank::try_from(row).map_err(BoardPositionFromStrErrors::InvalidRowFormat)?;
+        Ok(BoardPosition { column, row })
     }
 }
 
@@ -53,9 +56,9 @@ pub enum BoardPositionFromIsizeErrors {
     NegativeFile,
     #[error("The rank (row) passed in is negative.")]
     NegativeRank,
-    #[error("The rank (row) passed in is greater than 7.")]
+    #[error("The rank (row) passed in is greater than 15.")]
     RankTooHigh,
-    #[error("The file (column) passed in is greater than 7.")]
+    #[error("The file (column) passed in is greater than 15.")]
     FileTooHigh,
 }
 
@@ -84,9 +87,9 @@ impl TryFrom<(isize, isize)> for BoardPosition {
 
 #[derive(Debug, Error)]
 pub enum BoardPositionFromUsizeErrors {
-    #[error("The rank (row) passed in is greater than 7.")]
+    #[error("The rank (row) passed in is greater than 15.")]
     RankTooHigh,
-    #[error("The file (column) passed in is greater than 7.")]
+    #[error("The file (column) passed in is greater than 15.")]
     FileTooHigh,
 }
 
@@ -1,9 +1,10 @@
-use super::{ArrayIndex, FromArrayIndexError};
+use super::{ArrayIndex, FromArrayIndexError, MAX_FILES};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "char")]
 pub struct ChessFile(char);
 impl std::fmt::Display for ChessFile {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -13,7 +14,7 @@ impl std::fmt::Display for ChessFile {
 
 #[derive(Debug, Error)]
 pub enum FileInstancingErrors {
-    #[error("The column must be a char between 'a' and 'h'!")]
+    #[error("The column must be a char between 'a' and 'p'!")]
     InvalidColumnChar,
 }
 
@@ -21,7 +22,7 @@ impl TryFrom<char> for ChessFile {
     type Error = FileInstancingErrors;
     fn try_from(column: char) -> Result<Self, Self::Error> {
         match column {
-            'a'..='h' => Ok(ChessFile(column)),
+            'a'..='p' => Ok(ChessFile(column)),
             _ => Err(FileInstancingErrors::InvalidColumnChar),
         }
     }
@@ -34,10 +35,10 @@ impl ArrayIndex for ChessFile {
     }
 
     fn from_index(value: usize) -> Result<Self, FromArrayIndexError> {
-        match value {
-            0..=7 => Ok(ChessFile(((value as u8) + 97) as char)),
-            8..=usize::MAX => Err(FromArrayIndexError::IndexTooBig(7)),
-            _ => unreachable!(),
+        if value < MAX_FILES {
+            Ok(ChessFile(((value as u8) + 97) as char))
+        } else {
+            Err(FromArrayIndexError::IndexTooBig(MAX_FILES - 1))
         }
     }
 }
@@ -27,9 +27,7 @@ impl ChessPiece {
 
     /// Retrieves the piece position in the form of a tuple of array indeces.
     pub fn position(&self) -> (usize, usize) {
-        self.position
-            .clone()
-            .into()
+        self.position.clone().into()
     }
 
     /// Retrieves the piece position.
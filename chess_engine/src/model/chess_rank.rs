@@ -1,19 +1,20 @@
-use super::{ArrayIndex, FromArrayIndexError};
+use super::{ArrayIndex, FromArrayIndexError, MAX_RANKS};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "usize")]
 pub struct ChessRank(usize);
 impl std::fmt::Display for ChessRank {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.0 + 1)
+        write!(f, "{}", self.0)
     }
 }
 
 #[derive(Debug, Error)]
 pub enum RankInstancingErrors {
-    #[error("The rank (row) must be a number between 1 and 8!")]
+    #[error("The rank (row) must be a number between 1 and 16!")]
     InvalidRowNumber,
 }
 
@@ -21,7 +22,7 @@ impl TryFrom<usize> for ChessRank {
     type Error = RankInstancingErrors;
     fn try_from(row: usize) -> Result<Self, Self::Error> {
         match row {
-            1..=8 => Ok(ChessRank(row)),
+            1..=MAX_RANKS => Ok(ChessRank(row)),
             _ => Err(RankInstancingErrors::InvalidRowNumber),
         }
     }
@@ -33,10 +34,10 @@ impl ArrayIndex for ChessRank {
     }
 
     fn from_index(value: usize) -> Result<Self, FromArrayIndexError> {
-        match value {
-            0..=7 => Ok(ChessRank(value + 1)),
-            8..=usize::MAX => Err(FromArrayIndexError::IndexTooBig(7)),
-            _ => unreachable!(),
+        if value < MAX_RANKS {
+            Ok(ChessRank(value + 1))
+        } else {
+            Err(FromArrayIndexError::IndexTooBig(MAX_RANKS - 1))
         }
     }
 }
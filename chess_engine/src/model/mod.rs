@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 mod board;
+mod board_dimensions;
 mod board_movement;
 mod board_path;
 mod board_position;
@@ -9,10 +10,12 @@ mod chess_cell;
 mod chess_file;
 mod chess_piece;
 mod chess_rank;
+mod movement_path;
 mod piece_colors;
 mod piece_types;
 
 pub use self::board::*;
+pub use self::board_dimensions::*;
 pub use self::board_movement::*;
 pub use self::board_path::*;
 pub use self::board_position::*;
@@ -21,7 +24,7 @@ pub use self::chess_cell::*;
 pub use self::chess_file::*;
 pub use self::chess_piece::*;
 pub use self::chess_rank::*;
-pub use self::piece_colors::*;
+pub use self::movement_path::*;
 pub use self::piece_colors::*;
 pub use self::piece_types::*;
 
@@ -0,0 +1,21 @@
+use super::BoardPath;
+use serde::{Deserialize, Serialize};
+
+/// The kind of movements a piece can make along a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementKind {
+    /// The piece can move to empty cells and capture foe pieces.
+    MoveAndCapture,
+    /// The piece can only move to empty cells, like a pawn moving forward.
+    MoveOnly,
+    /// The piece can only move to a cell to capture a foe piece, like a pawn capturing diagonally.
+    CaptureOnly,
+}
+
+/// A path a piece can take, ordered from the closest to the farthest cell from the piece.
+/// Collisions with other pieces are not considered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovementPath {
+    pub path: BoardPath,
+    pub kind: MovementKind,
+}
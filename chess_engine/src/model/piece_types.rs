@@ -8,4 +8,45 @@ pub enum PieceTypes {
     Bishop,
     Queen,
     King,
+    /// A piece that's not on standard chess, written with its letter in uppercase.
+    /// How it moves is defined by the fairy pieces of the board it's on.
+    Fairy(char),
+}
+
+impl PieceTypes {
+    /// Moves like a bishop or a knight.
+    pub const ARCHBISHOP: PieceTypes = PieceTypes::Fairy('A');
+    /// Moves like a rook or a knight.
+    pub const CHANCELLOR: PieceTypes = PieceTypes::Fairy('C');
+    /// Moves like a queen or a knight.
+    pub const AMAZON: PieceTypes = PieceTypes::Fairy('M');
+}
+
+impl PieceTypes {
+    /// Get's the letter used to write this kind of piece, in uppercase.
+    pub fn symbol(&self) -> char {
+        match self {
+            PieceTypes::Pawn => 'P',
+            PieceTypes::Rook => 'R',
+            PieceTypes::Knight => 'N',
+            PieceTypes::Bishop => 'B',
+            PieceTypes::Queen => 'Q',
+            PieceTypes::King => 'K',
+            PieceTypes::Fairy(symbol) => *symbol,
+        }
+    }
+
+    /// Get's the standard kind of piece written with the given letter, ignoring its case.
+    /// Fairy pieces depend on the board, see `Board::piece_kind`.
+    pub fn from_symbol(symbol: char) -> Option<PieceTypes> {
+        match symbol.to_ascii_uppercase() {
+            'P' => Some(PieceTypes::Pawn),
+            'R' => Some(PieceTypes::Rook),
+            'N' => Some(PieceTypes::Knight),
+            'B' => Some(PieceTypes::Bishop),
+            'Q' => Some(PieceTypes::Queen),
+            'K' => Some(PieceTypes::King),
+            _ => None,
+        }
+    }
 }
@@ -1,13 +1,14 @@
 use super::*;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MovementSuccess {
     NormalMovement,
     CheckmateMovement,
     StalemateMovement,
+    KingCaptureMovement,
 }
 
 #[derive(Debug, Error, Serialize)]
@@ -22,105 +23,353 @@ pub enum MovementError {
     MovementDoesntRemoveCheck,
     #[error("The king can't castle because the rook already moved!")]
     CantCastleBecauseTheRookMoved,
+    #[error("It's not the turn of this piece color!")]
+    NotYourTurn,
+    #[error("The piece to move is not on the board.")]
+    PieceNotOnBoard,
+    #[error("Only a pawn reaching the last rank can promote, and only to a piece of the board that's not a pawn nor a king.")]
+    InvalidPromotion,
+    #[error("The board is inconsistent: {0}")]
+    InconsistentBoard(#[from] BoardProblem),
 }
 
-pub fn move_piece(
-    movement: BoardMovement,
-    board: &mut Board,
-) -> Result<MovementSuccess, MovementError> {
-    let BoardMovement { piece, destination } = movement;
-    let piece_color = piece.color().clone();
-
-    let piece_in_dest = board.get_piece(&destination);
-    let destination_has_piece = piece_in_dest.is_some();
-    let both_same_color = if let Some(p) = piece_in_dest {
-        p.color() == &piece_color
+/// Everything that happened on a movement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub from: BoardPosition,
+    pub to: BoardPosition,
+    /// The piece that moved, as it was before moving.
+    pub piece: ChessPiece,
+    /// The piece that was captured, including pawns captured en passant.
+    pub captured_piece: Option<ChessPiece>,
+    /// The side the king castled to, if the movement was a castle.
+    pub castling: Option<ChessBoardDirections>,
+    /// The piece the pawn became, if it promoted.
+    pub promotion: Option<PieceTypes>,
+    pub gives_check: bool,
+    /// The movement in Standard Algebraic Notation, like `Nxe5+` or `O-O`.
+    pub san: String,
+    /// The Zobrist hash of the position after the movement. It's serialized as hexadecimal.
+    #[serde(with = "crate::zobrist::hash_as_hex")]
+    pub hash: u64,
+    pub outcome: MovementSuccess,
+}
+
+/// A movement made on a copy of the board.
+pub(crate) struct AppliedMovement {
+    pub board: Board,
+    pub captured_piece: Option<ChessPiece>,
+    pub castling: Option<ChessBoardDirections>,
+    pub promotion: Option<PieceTypes>,
+}
+
+pub fn move_piece(movement: BoardMovement, board: &mut Board) -> Result<MoveRecord, MovementError> {
+    let BoardMovement {
+        piece,
+        destination,
+        promotion,
+    } = movement;
+    let opponent_piece_color = piece.color().opponent();
+
+    if piece.color() != &board.side_to_move {
+        return Err(MovementError::NotYourTurn);
+    }
+
+    let applied_movement = apply_movement(board, &piece, &destination, promotion)?;
+    let moved_board = &applied_movement.board;
+
+    let outcome = if moved_board.is_checkmate(&opponent_piece_color) {
+        MovementSuccess::CheckmateMovement
+    } else if moved_board.is_stalemate(&opponent_piece_color) {
+        MovementSuccess::StalemateMovement
     } else {
-        false
+        MovementSuccess::NormalMovement
     };
 
-    let is_king = matches!(piece.kind(), PieceTypes::King);
-    if destination_has_piece && both_same_color {
-        return Err(MovementError::DestinationCellOccupied);
-    }
+    let record = MoveRecord::new(board, piece, destination, &applied_movement, outcome);
+    *board = applied_movement.board;
+    Ok(record)
+}
+
+impl MoveRecord {
+    /// Creates the record of a movement made on the given board.
+    pub(crate) fn new(
+        board: &Board,
+        piece: ChessPiece,
+        destination: BoardPosition,
+        applied_movement: &AppliedMovement,
+        outcome: MovementSuccess,
+    ) -> Self {
+        let AppliedMovement {
+            board: moved_board,
+            captured_piece,
+            castling,
+            promotion,
+        } = applied_movement;
+
+        let gives_check = moved_board.is_in_check(&piece.color().opponent());
+        let san = get_san(
+            board,
+            &piece,
+            &destination,
+            applied_movement,
+            outcome == MovementSuccess::CheckmateMovement,
+        );
 
-    if let Some(check_positions) = board.get_check_positions() {
-        match (is_king, check_positions.contains(&destination)) {
-            (true, true) => Err(MovementError::MovementWouldCauseCheck),
-            (false, false) => Err(MovementError::MovementDoesntRemoveCheck),
-            // The destination is a valid piece destination according to the piece type.
-            // King: The destination is a position where he's not in check.
-            // Any other piece: The destination blocks a check path.
-            (is_king, _) => inner_move_piece(is_king, piece, destination, board),
+        MoveRecord {
+            from: piece.board_position().clone(),
+            to: destination,
+            piece,
+            captured_piece: captured_piece.clone(),
+            castling: *castling,
+            promotion: *promotion,
+            gives_check,
+            san,
+            hash: moved_board.zobrist_hash(),
+            outcome,
         }
-    } else {
-        inner_move_piece(is_king, piece, destination, board)
     }
 }
 
-fn inner_move_piece(
-    is_king: bool,
-    piece: ChessPiece,
-    destination: BoardPosition,
-    board: &mut Board,
-) -> Result<MovementSuccess, MovementError> {
-    let piece_color = piece.color().clone();
-    let opponent_piece_color = piece_color.opponent();
-
-    let movement_pattern = board.get_movement_paths(&piece);
-    let movement_positions: Vec<BoardPosition> = movement_pattern
-        .into_iter()
-        .flat_map(|path| path.0)
-        .collect();
-
-    let castling_state = board.get_castling_state(&piece_color);
-    let mut castle_direction = None;
-    if let (
-        true,
-        Some(CastlingState {
-            can_use_left_rook,
-            can_use_right_rook,
-        }),
-    ) = (is_king, castling_state)
-    {
-        let is_on_starting_positions = is_king_on_starting_position(&piece);
-        if is_on_starting_positions {
-            let wants_to_castle_left =
-                check_castle_left(&piece.position().try_into().unwrap(), &destination);
-            let wants_to_castle_right =
-                check_castle_right(&piece.position().try_into().unwrap(), &destination);
-
-            match (wants_to_castle_left, can_use_left_rook) {
-                (true, true) => castle_direction = Some(ChessBoardDirections::Left),
-                (true, false) => return Err(MovementError::CantCastleBecauseTheRookMoved),
-                _ => {}
-            }
-
-            match (wants_to_castle_right, can_use_right_rook) {
-                (true, true) => castle_direction = Some(ChessBoardDirections::Right),
-                (true, false) => return Err(MovementError::CantCastleBecauseTheRookMoved),
-                _ => {}
-            }
+/// Makes the movement on a copy of the board after checking it's valid, without considering
+/// whose turn it is.
+/// The given board is never modified.
+pub(crate) fn apply_movement(
+    board: &Board,
+    piece: &ChessPiece,
+    destination: &BoardPosition,
+    promotion: Option<PieceTypes>,
+) -> Result<AppliedMovement, MovementError> {
+    let piece_color = piece.color();
+
+    if board.get_piece(piece.board_position()).as_ref() != Some(piece) {
+        return Err(MovementError::PieceNotOnBoard);
+    }
+
+    if !board.dimensions().contains(destination) {
+        return Err(MovementError::DestinationDoesntFollowMovementPattern);
+    }
+
+    if let Some(piece_in_dest) = board.get_piece(destination) {
+        if piece_in_dest.color() == piece_color {
+            return Err(MovementError::DestinationCellOccupied);
         }
-    } else if !movement_positions.contains(&destination) {
+    }
+
+    let castling = get_castle_direction(board, piece, destination)?;
+    if castling.is_none()
+        && !board
+            .get_pseudo_legal_positions(piece)
+            .contains(destination)
+    {
         return Err(MovementError::DestinationDoesntFollowMovementPattern);
     }
 
-    board.move_piece(piece, &destination, castle_direction);
+    let promotion = get_promotion(board, piece, destination, promotion)?;
+    let mut moved_board = board.clone();
+    let captured_piece = moved_board.move_piece(piece.clone(), destination, castling, promotion)?;
 
-    let king_position = if is_king {
-        destination
-    } else {
-        board.get_king_position(&piece_color)
+    if moved_board.is_in_check(piece_color) {
+        if board.is_in_check(piece_color) {
+            return Err(MovementError::MovementDoesntRemoveCheck);
+        }
+        return Err(MovementError::MovementWouldCauseCheck);
+    }
+
+    Ok(AppliedMovement {
+        board: moved_board,
+        captured_piece,
+        castling,
+        promotion,
+    })
+}
+
+/// Get's the kinds of pieces pawns can promote to on the board: queens, rooks, bishops, knights
+/// and the fairy pieces of the board.
+pub(crate) fn get_promotion_kinds(board: &Board) -> Vec<PieceTypes> {
+    let mut kinds = vec![
+        PieceTypes::Queen,
+        PieceTypes::Rook,
+        PieceTypes::Bishop,
+        PieceTypes::Knight,
+    ];
+    kinds.extend(board.fairy_pieces.iter().map(FairyPiece::kind));
+    kinds
+}
+
+/// Get's the piece the pawn becomes when moving to the destination.
+/// Pawns reaching the last rank become queens if no promotion is given, and can only become one
+/// of the `get_promotion_kinds` of the board.
+pub(crate) fn get_promotion(
+    board: &Board,
+    piece: &ChessPiece,
+    destination: &BoardPosition,
+    promotion: Option<PieceTypes>,
+) -> Result<Option<PieceTypes>, MovementError> {
+    let (white_back_rank, black_back_rank) = board.dimensions().back_ranks();
+    let (destination_row, _) = destination.into();
+    let last_rank = match piece.color() {
+        PieceColors::Black => white_back_rank,
+        PieceColors::White => black_back_rank,
     };
+    let promotes = piece.kind() == &PieceTypes::Pawn && destination_row == last_rank;
 
-    if board.position_in_check(&king_position, &piece_color) {
-        Err(MovementError::MovementDoesntRemoveCheck)
-    } else if board.is_checkmate(&opponent_piece_color) {
-        Ok(MovementSuccess::CheckmateMovement)
-    } else if board.is_stalemate(&opponent_piece_color) {
-        Ok(MovementSuccess::StalemateMovement)
+    match (promotes, promotion) {
+        (true, Some(kind)) if !get_promotion_kinds(board).contains(&kind) => {
+            Err(MovementError::InvalidPromotion)
+        }
+        (true, promotion) => Ok(Some(promotion.unwrap_or(PieceTypes::Queen))),
+        (false, Some(_)) => Err(MovementError::InvalidPromotion),
+        (false, None) => Ok(None),
+    }
+}
+
+/// Get's the direction the piece castles to when moving to the destination, if it's a king
+/// castling.
+pub(crate) fn get_castle_direction(
+    board: &Board,
+    piece: &ChessPiece,
+    destination: &BoardPosition,
+) -> Result<Option<ChessBoardDirections>, MovementError> {
+    let dimensions = board.dimensions();
+    let Some(CastlingState {
+        can_use_left_rook,
+        can_use_right_rook,
+    }) = board.get_castling_state(piece.color())
+    else {
+        return Ok(None);
+    };
+    if !is_king_on_starting_position(piece, &dimensions) {
+        return Ok(None);
+    }
+
+    let origin = piece.board_position();
+    let (direction, can_use_rook) = if check_castle_left(origin, destination, &dimensions) {
+        (ChessBoardDirections::Left, can_use_left_rook)
+    } else if check_castle_right(origin, destination, &dimensions) {
+        (ChessBoardDirections::Right, can_use_right_rook)
     } else {
-        Ok(MovementSuccess::NormalMovement)
+        return Ok(None);
+    };
+
+    if !can_use_rook {
+        return Err(MovementError::CantCastleBecauseTheRookMoved);
+    }
+    if !is_castling_path_empty(board, piece, &direction) {
+        return Err(MovementError::DestinationDoesntFollowMovementPattern);
+    }
+    if board.is_in_check(piece.color()) {
+        return Err(MovementError::MovementDoesntRemoveCheck);
+    }
+
+    // The king can't pass through an attacked cell while castling.
+    let (row, column) = piece.position();
+    let (_, destination_column) = destination.into();
+    let passes_through_check = (column.min(destination_column)..=column.max(destination_column))
+        .filter_map(|column| BoardPosition::try_from((row, column)).ok())
+        .any(|position| board.position_in_check(&position, piece.color()));
+    if passes_through_check {
+        return Err(MovementError::MovementWouldCauseCheck);
+    }
+
+    Ok(Some(direction))
+}
+
+/// Checks that there are no pieces between the king and the rook, nor on the cells they end on.
+fn is_castling_path_empty(
+    board: &Board,
+    king: &ChessPiece,
+    direction: &ChessBoardDirections,
+) -> bool {
+    let dimensions = board.dimensions();
+    let (row, king_file) = king.position();
+    let rook_file = match direction {
+        ChessBoardDirections::Right => dimensions.files.saturating_sub(1),
+        _ => 0,
+    };
+    let (king_destination_file, rook_destination_file) = castling_files(direction, &dimensions);
+
+    let files = [
+        king_file,
+        rook_file,
+        king_destination_file,
+        rook_destination_file,
+    ];
+    let first_file = files.iter().min().copied().unwrap_or(king_file);
+    let last_file = files.iter().max().copied().unwrap_or(king_file);
+
+    (first_file..=last_file)
+        .filter(|column| *column != king_file && *column != rook_file)
+        .all(|column| {
+            BoardPosition::try_from((row, column))
+                .map_or(false, |position| board.get_piece(&position).is_none())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn promote(
+        fairy_pieces: Vec<FairyPiece>,
+        promotion: Option<PieceTypes>,
+    ) -> Result<MoveRecord, MovementError> {
+        let mut board = BoardBuilder::from_diagram(
+            "\
+            k . . . . . . .
+            . . . . P . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            K . . . . . . .",
+        )
+        .unwrap()
+        .fairy_pieces(fairy_pieces)
+        .build()
+        .unwrap();
+        let movement = BoardMovement {
+            piece: board.get_piece(&"e7".try_into().unwrap()).unwrap(),
+            destination: "e8".try_into().unwrap(),
+            promotion,
+        };
+
+        move_piece(movement, &mut board)
+    }
+
+    #[test]
+    fn pawns_promote_to_the_pieces_of_the_board() {
+        assert_eq!(
+            promote(vec![], None).unwrap().promotion,
+            Some(PieceTypes::Queen)
+        );
+        for kind in [
+            PieceTypes::Queen,
+            PieceTypes::Rook,
+            PieceTypes::Bishop,
+            PieceTypes::Knight,
+        ] {
+            assert_eq!(promote(vec![], Some(kind)).unwrap().promotion, Some(kind));
+        }
+        for kind in [
+            PieceTypes::Pawn,
+            PieceTypes::King,
+            PieceTypes::AMAZON,
+            PieceTypes::Fairy('Q'),
+        ] {
+            assert!(matches!(
+                promote(vec![], Some(kind)),
+                Err(MovementError::InvalidPromotion)
+            ));
+        }
+
+        let record = promote(vec![FairyPiece::AMAZON], Some(PieceTypes::AMAZON)).unwrap();
+        assert_eq!(record.promotion, Some(PieceTypes::AMAZON));
+        assert_eq!(record.san, "e8=M+");
+        assert!(matches!(
+            promote(vec![FairyPiece::AMAZON], Some(PieceTypes::ARCHBISHOP)),
+            Err(MovementError::InvalidPromotion)
+        ));
     }
 }
@@ -0,0 +1,421 @@
+use super::*;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+use thiserror::Error;
+
+/// The size in bytes of each entry of a Polyglot book.
+const POLYGLOT_ENTRY_SIZE: usize = 16;
+/// How many plies of each game the book builder looks at when no other amount is given.
+pub const DEFAULT_BOOK_DEPTH: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum OpeningBookError {
+    #[error("The book has {0} bytes, which is not a multiple of the size of its entries.")]
+    InvalidLength(usize),
+    #[error("The book file can't be read or written: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// An entry of a Polyglot opening book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookEntry {
+    /// The Zobrist hash of the position the movement is played on.
+    pub key: u64,
+    /// The movement, encoded the way Polyglot does.
+    pub movement: u16,
+    /// How often the movement should be played compared to the others of the position.
+    pub weight: u16,
+    pub learn: u32,
+}
+
+/// A movement of an opening book for a position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookMovement {
+    pub movement: BoardMovement,
+    pub weight: u16,
+}
+
+/// An opening book in the Polyglot `.bin` format.
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    /// The entries sorted by their key.
+    entries: Vec<BookEntry>,
+}
+
+impl OpeningBook {
+    /// Reads a book from the bytes of a Polyglot `.bin` file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OpeningBookError> {
+        if bytes.len() % POLYGLOT_ENTRY_SIZE != 0 {
+            return Err(OpeningBookError::InvalidLength(bytes.len()));
+        }
+
+        let entries = bytes
+            .chunks_exact(POLYGLOT_ENTRY_SIZE)
+            .map(|chunk| {
+                let mut key = [0; 8];
+                let mut movement = [0; 2];
+                let mut weight = [0; 2];
+                let mut learn = [0; 4];
+                key.copy_from_slice(&chunk[0..8]);
+                movement.copy_from_slice(&chunk[8..10]);
+                weight.copy_from_slice(&chunk[10..12]);
+                learn.copy_from_slice(&chunk[12..16]);
+
+                BookEntry {
+                    key: u64::from_be_bytes(key),
+                    movement: u16::from_be_bytes(movement),
+                    weight: u16::from_be_bytes(weight),
+                    learn: u32::from_be_bytes(learn),
+                }
+            })
+            .collect();
+
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Creates a book with the given entries, in any order.
+    pub fn from_entries(mut entries: Vec<BookEntry>) -> Self {
+        entries.sort_by(|a, b| a.key.cmp(&b.key).then(b.weight.cmp(&a.weight)));
+        OpeningBook { entries }
+    }
+
+    /// Reads a Polyglot `.bin` file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, OpeningBookError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Get's the book in the Polyglot `.bin` format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries
+            .iter()
+            .flat_map(|entry| {
+                [
+                    &entry.key.to_be_bytes()[..],
+                    &entry.movement.to_be_bytes(),
+                    &entry.weight.to_be_bytes(),
+                    &entry.learn.to_be_bytes(),
+                ]
+                .concat()
+            })
+            .collect()
+    }
+
+    /// Writes the book as a Polyglot `.bin` file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OpeningBookError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[BookEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get's the movements the book has for the position, from the most to the least played.
+    /// Movements that are not legal on the board are skipped.
+    pub fn get_movements(&self, board: &Board) -> Vec<BookMovement> {
+        if board.dimensions() != BoardDimensions::STANDARD {
+            return vec![];
+        }
+
+        let key = board.zobrist_hash();
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+
+        self.entries[start..end]
+            .iter()
+            .filter_map(|entry| {
+                let movement = decode_movement(board, entry.movement)?;
+                let is_legal = movement.piece.color() == &board.side_to_move
+                    && apply_movement(
+                        board,
+                        &movement.piece,
+                        &movement.destination,
+                        movement.promotion,
+                    )
+                    .is_ok();
+
+                is_legal.then_some(BookMovement {
+                    movement,
+                    weight: entry.weight,
+                })
+            })
+            .collect()
+    }
+
+    /// Chooses one of the movements of the book for the position, where the chance of playing
+    /// each movement is given by its weight.
+    /// Returns `None` if the book has no movement with weight for the position.
+    pub fn choose_movement<R: Rng + ?Sized>(
+        &self,
+        board: &Board,
+        rng: &mut R,
+    ) -> Option<BoardMovement> {
+        let movements = self.get_movements(board);
+        let total_weight: u32 = movements
+            .iter()
+            .map(|movement| movement.weight as u32)
+            .sum();
+        if total_weight == 0 {
+            return None;
+        }
+
+        let mut choice = rng.gen_range(0..total_weight);
+        movements
+            .into_iter()
+            .find(|movement| {
+                let weight = movement.weight as u32;
+                if choice < weight {
+                    return true;
+                }
+                choice -= weight;
+                false
+            })
+            .map(|movement| movement.movement)
+    }
+}
+
+/// Builds an opening book from the movements played on a set of games.
+/// Each time a movement is played it scores 2 if the player that made it won the game, 1 if the
+/// game was drawn and nothing if it was lost.
+#[derive(Debug, Clone)]
+pub struct OpeningBookBuilder {
+    max_ply: usize,
+    /// The score of each movement, by the hash of the position and the encoded movement.
+    scores: HashMap<(u64, u16), u64>,
+}
+
+impl Default for OpeningBookBuilder {
+    fn default() -> Self {
+        OpeningBookBuilder::new(DEFAULT_BOOK_DEPTH)
+    }
+}
+
+impl OpeningBookBuilder {
+    /// Creates a builder that only looks at the first `max_ply` movements of each game.
+    pub fn new(max_ply: usize) -> Self {
+        OpeningBookBuilder {
+            max_ply,
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Adds the movements of the main line of the game.
+    /// Games of other variants, on other boards or without a known result are skipped.
+    pub fn add_game(&mut self, game: &PgnGame) {
+        let Some(result) = game.result else {
+            return;
+        };
+        let tree = &game.tree;
+        let is_standard = tree.variant() == GameVariant::Standard
+            && tree.node(tree.root()).map_or(false, |root| {
+                root.board().dimensions() == BoardDimensions::STANDARD
+            });
+        if !is_standard {
+            return;
+        }
+
+        let nodes: Vec<&GameNode> = tree
+            .mainline()
+            .into_iter()
+            .filter_map(|id| tree.node(id))
+            .collect();
+        for pair in nodes.windows(2) {
+            let (position, played) = (pair[0], pair[1]);
+            if played.ply() > self.max_ply {
+                break;
+            }
+            let Some(record) = played.record() else {
+                continue;
+            };
+            let Some(movement) = encode_movement(record) else {
+                continue;
+            };
+
+            let score = match result {
+                GameResult::Draw => 1,
+                GameResult::WhiteWins if record.piece.color() == &PieceColors::White => 2,
+                GameResult::BlackWins if record.piece.color() == &PieceColors::Black => 2,
+                _ => 0,
+            };
+            *self
+                .scores
+                .entry((position.board().zobrist_hash(), movement))
+                .or_default() += score;
+        }
+    }
+
+    pub fn add_games<'a>(&mut self, games: impl IntoIterator<Item = &'a PgnGame>) {
+        for game in games {
+            self.add_game(game);
+        }
+    }
+
+    /// Creates the book. The scores are scaled down if they don't fit on the weights of the
+    /// entries.
+    pub fn build(&self) -> OpeningBook {
+        let max_score = self.scores.values().copied().max().unwrap_or(0);
+        let scale = |score: u64| {
+            if max_score > u16::MAX as u64 {
+                (score * u16::MAX as u64 / max_score) as u16
+            } else {
+                score as u16
+            }
+        };
+
+        let entries = self
+            .scores
+            .iter()
+            .map(|(&(key, movement), &score)| BookEntry {
+                key,
+                movement,
+                weight: scale(score),
+                learn: 0,
+            })
+            .collect();
+
+        OpeningBook::from_entries(entries)
+    }
+}
+
+/// Get's the movement written the way Polyglot does: the destination on the lowest 6 bits, then
+/// the origin and the promotion. Castling is written as the king capturing its own rook.
+fn decode_movement(board: &Board, movement: u16) -> Option<BoardMovement> {
+    let square = |index: u16| -> Option<BoardPosition> {
+        BoardPosition::try_from((((index >> 3) & 7) as usize, (index & 7) as usize)).ok()
+    };
+    let origin = square(movement >> 6)?;
+    let mut destination = square(movement)?;
+    let promotion = match (movement >> 12) & 7 {
+        0 => None,
+        1 => Some(PieceTypes::Knight),
+        2 => Some(PieceTypes::Bishop),
+        3 => Some(PieceTypes::Rook),
+        4 => Some(PieceTypes::Queen),
+        _ => return None,
+    };
+
+    let piece = board.get_piece(&origin)?;
+    let captures_own_rook = board.get_piece(&destination).map_or(false, |rook| {
+        rook.kind() == &PieceTypes::Rook && rook.color() == piece.color()
+    });
+    if piece.kind() == &PieceTypes::King && captures_own_rook {
+        let (row, rook_column) = (&destination).into();
+        let (_, king_column) = (&origin).into();
+        let direction = if rook_column > king_column {
+            ChessBoardDirections::Right
+        } else {
+            ChessBoardDirections::Left
+        };
+        let (king_file, _) = castling_files(&direction, &board.dimensions());
+        destination = (row, king_file).try_into().ok()?;
+    }
+
+    Some(BoardMovement {
+        piece,
+        destination,
+        promotion,
+    })
+}
+
+/// Get's the movement encoded the way Polyglot does, or `None` if it can't be encoded.
+fn encode_movement(record: &MoveRecord) -> Option<u16> {
+    let square = |(row, column): (usize, usize)| -> Option<u16> {
+        (row < 8 && column < 8).then_some((row * 8 + column) as u16)
+    };
+    let (row, column) = (&record.to).into();
+    let destination = match record.castling {
+        Some(ChessBoardDirections::Right) => (row, 7),
+        Some(_) => (row, 0),
+        None => (row, column),
+    };
+    let promotion = match record.promotion {
+        None => 0,
+        Some(PieceTypes::Knight) => 1,
+        Some(PieceTypes::Bishop) => 2,
+        Some(PieceTypes::Rook) => 3,
+        Some(PieceTypes::Queen) => 4,
+        Some(_) => return None,
+    };
+
+    Some(promotion << 12 | square((&record.from).into())? << 6 | square(destination)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get's the hash of the position reached playing the movements from the starting position.
+    fn hash_after(movements: &[&str]) -> u64 {
+        let mut board = get_starting_board();
+        for movement in movements {
+            let movement = board.parse_uci_move(movement).unwrap();
+            move_piece(movement, &mut board).unwrap();
+        }
+        board.zobrist_hash()
+    }
+
+    #[test]
+    fn hashes_match_the_polyglot_reference_keys() {
+        assert_eq!(hash_after(&[]), 0x463b96181691fc9c);
+        assert_eq!(hash_after(&["e2e4"]), 0x823c9b50fd114196);
+        assert_eq!(hash_after(&["e2e4", "d7d5"]), 0x0756b94461c50fb0);
+        assert_eq!(hash_after(&["e2e4", "d7d5", "e4e5"]), 0x662fafb965db29d4);
+        assert_eq!(
+            hash_after(&["e2e4", "d7d5", "e4e5", "f7f5"]),
+            0x22a48b5a8e47ff78
+        );
+        assert_eq!(
+            hash_after(&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2"]),
+            0x652a607ca3f242c1
+        );
+        assert_eq!(
+            hash_after(&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2", "e8f7"]),
+            0x00fdd303c946bdd9
+        );
+        assert_eq!(
+            hash_after(&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4"]),
+            0x3c8123ea7b067637
+        );
+        assert_eq!(
+            hash_after(&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4", "b4c3", "a1a3"]),
+            0x5c3f9b829b279560
+        );
+    }
+
+    #[test]
+    fn built_books_keep_the_movements_of_the_games() {
+        let games = PgnGame::parse_all(
+            "[Result \"1-0\"]\n\n1. e4 e5 2. Nf3 1-0\n\n[Result \"0-1\"]\n\n1. d4 d5 0-1\n",
+        )
+        .unwrap();
+        let mut builder = OpeningBookBuilder::new(2);
+        builder.add_games(&games);
+
+        let book = OpeningBook::from_bytes(&builder.build().to_bytes()).unwrap();
+        let movements: Vec<String> = book
+            .get_movements(&get_starting_board())
+            .into_iter()
+            .map(|BookMovement { movement, .. }| {
+                format!(
+                    "{}{}",
+                    movement.piece.board_position(),
+                    movement.destination
+                )
+            })
+            .collect();
+
+        assert_eq!(book.len(), 4);
+        assert!(movements.contains(&"e2e4".to_string()));
+        assert!(movements.contains(&"d2d4".to_string()));
+    }
+}
@@ -0,0 +1,574 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The maximum length of the movement lines of an exported PGN.
+const PGN_LINE_LENGTH: usize = 80;
+
+#[derive(Debug, Error)]
+pub enum PgnError {
+    #[error("There's no game on the PGN.")]
+    NoGame,
+    #[error("The tag `{0}` is not written as `[Name \"Value\"]`.")]
+    InvalidHeader(String),
+    #[error("The FEN tag is invalid: {0}")]
+    InvalidFen(#[from] FenError),
+    #[error("The movement number {0} is invalid: {1}")]
+    InvalidMove(usize, SanError),
+    #[error("The movement number {0} can't be played: {1}")]
+    IllegalMove(usize, GameError),
+    #[error("A variation was closed without being opened, or opened before any movement.")]
+    InvalidVariation,
+    #[error("A variation was never closed.")]
+    UnclosedVariation,
+    #[error("A comment was never closed.")]
+    UnclosedComment,
+}
+
+/// A game written in Portable Game Notation, with its tags, every variation and the notes on
+/// each movement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgnGame {
+    /// The tag pairs, in the order they are written.
+    pub headers: Vec<(String, String)>,
+    pub tree: GameTree,
+    /// The result of the game, or `None` if it's still going on or unknown.
+    pub result: Option<GameResult>,
+}
+
+/// A piece of the movement text of a PGN.
+#[derive(Debug)]
+enum Token {
+    Comment(String),
+    Nag(u8),
+    Move(String),
+    StartVariation,
+    EndVariation,
+    Result(Option<GameResult>),
+}
+
+impl PgnGame {
+    /// Creates a game record with the seven tags every PGN has, set to unknown values.
+    /// The starting position is written as a FEN tag if it's not the standard one.
+    pub fn new(tree: GameTree) -> Self {
+        let mut headers: Vec<(String, String)> = [
+            ("Event", "?"),
+            ("Site", "?"),
+            ("Date", "????.??.??"),
+            ("Round", "?"),
+            ("White", "?"),
+            ("Black", "?"),
+            ("Result", "*"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+        let root = tree.node(tree.root()).map(|root| root.board().to_fen());
+        if let Some(fen) = root.filter(|fen| fen != STARTING_FEN) {
+            headers.push((String::from("SetUp"), String::from("1")));
+            headers.push((String::from("FEN"), fen));
+        }
+
+        PgnGame {
+            headers,
+            tree,
+            result: None,
+        }
+    }
+
+    /// Reads the first game of a PGN.
+    pub fn parse(pgn: &str) -> Result<Self, PgnError> {
+        PgnGame::parse_all(pgn)?
+            .into_iter()
+            .next()
+            .ok_or(PgnError::NoGame)
+    }
+
+    /// Reads every game of a PGN.
+    pub fn parse_all(pgn: &str) -> Result<Vec<Self>, PgnError> {
+        let mut reader = PgnReader { rest: pgn };
+        let mut games = vec![];
+        while let Some(game) = reader.read_game()? {
+            games.push(game);
+        }
+
+        Ok(games)
+    }
+
+    /// Get's the value of the tag with the given name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of a tag, adding it after the others if it's not there.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        match self.headers.iter_mut().find(|(header, _)| header == name) {
+            Some((_, old_value)) => *old_value = value.to_string(),
+            None => self.headers.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Get's the move number of the first position, from the FEN tag.
+    fn starting_fullmove_number(&self) -> usize {
+        self.header("FEN")
+            .and_then(|fen| Fen::try_from(fen).ok())
+            .map_or(1, |fen| fen.fullmove_number)
+    }
+
+    /// Get's the movement text of the line that starts on the given node, with the variations
+    /// of each movement after it.
+    fn write_line(&self, first: NodeId, tokens: &mut Vec<String>) {
+        let starting_fullmove_number = self.starting_fullmove_number();
+        let black_starts = self.tree.node(self.tree.root()).map_or(false, |root| {
+            root.board().side_to_move == PieceColors::Black
+        });
+
+        let mut id = first;
+        let mut needs_number = true;
+        while let Some(node) = self.tree.node(id) {
+            let Some(record) = node.record() else {
+                break;
+            };
+            let annotations = node.annotations();
+
+            if let Some(comment) = &annotations.starting_comment {
+                tokens.push(format!("{{ {} }}", comment));
+                needs_number = true;
+            }
+
+            let number = starting_fullmove_number + (node.ply() - 1 + black_starts as usize) / 2;
+            match record.piece.color() {
+                PieceColors::White => tokens.push(format!("{}.", number)),
+                PieceColors::Black if needs_number => tokens.push(format!("{}...", number)),
+                PieceColors::Black => {}
+            }
+            tokens.push(record.san.clone());
+            needs_number = false;
+
+            for nag in &annotations.nags {
+                tokens.push(format!("${}", nag));
+            }
+            if let Some(comment) = annotations.pgn_comment() {
+                tokens.push(format!("{{ {} }}", comment));
+                needs_number = true;
+            }
+
+            let siblings = node
+                .parent()
+                .and_then(|parent| self.tree.node(parent))
+                .map_or(&[][..], GameNode::children);
+            if siblings.first() == Some(&id) {
+                for variation in &siblings[1..] {
+                    tokens.push(String::from("("));
+                    self.write_line(*variation, tokens);
+                    tokens.push(String::from(")"));
+                    needs_number = true;
+                }
+            }
+
+            match node.children().first() {
+                Some(child) => id = *child,
+                None => break,
+            }
+        }
+    }
+}
+
+impl From<&ChessGame> for PgnGame {
+    fn from(game: &ChessGame) -> Self {
+        let mut tree = GameTree::new(game.initial_board().clone(), game.variant());
+        for record in game.history() {
+            let movement = BoardMovement {
+                piece: record.piece.clone(),
+                destination: record.to.clone(),
+                promotion: record.promotion,
+            };
+            if tree.play(movement).is_err() {
+                break;
+            }
+        }
+
+        let mut pgn = PgnGame::new(tree);
+        pgn.result = game.result();
+
+        let black_moves = game
+            .history()
+            .iter()
+            .filter(|record| record.piece.color() == &PieceColors::Black)
+            .count();
+        let starting_fullmove_number = game.fullmove_number().saturating_sub(black_moves).max(1);
+        if pgn.header("FEN").is_some() || starting_fullmove_number != 1 {
+            let fen = Fen {
+                board: game.initial_board().clone(),
+                halfmove_clock: 0,
+                fullmove_number: starting_fullmove_number,
+            };
+            pgn.set_header("SetUp", "1");
+            pgn.set_header("FEN", &fen.to_string());
+        }
+
+        pgn
+    }
+}
+
+/// Writes the game in the PGN export format, with the variations and notes of every movement.
+impl std::fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let result = self
+            .result
+            .map_or_else(|| String::from("*"), |result| result.to_string());
+
+        let mut has_result_header = false;
+        for (name, value) in &self.headers {
+            let value = if name == "Result" {
+                has_result_header = true;
+                &result
+            } else {
+                value
+            };
+            writeln!(
+                f,
+                "[{} \"{}\"]",
+                name,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+        }
+        if !has_result_header {
+            writeln!(f, "[Result \"{}\"]", result)?;
+        }
+        writeln!(f)?;
+
+        let mut tokens = vec![];
+        if let Some(root) = self.tree.node(self.tree.root()) {
+            if let Some(comment) = root.annotations().pgn_comment() {
+                tokens.push(format!("{{ {} }}", comment));
+            }
+            if let Some(first) = root.children().first() {
+                self.write_line(*first, &mut tokens);
+            }
+        }
+        tokens.push(result);
+
+        let mut line = String::new();
+        let mut previous = "";
+        for token in &tokens {
+            let needs_space = !line.is_empty() && previous != "(" && token != ")";
+            if needs_space && line.len() + 1 + token.len() > PGN_LINE_LENGTH {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            } else if needs_space {
+                line.push(' ');
+            }
+            line.push_str(token);
+            previous = token;
+        }
+        writeln!(f, "{}", line)
+    }
+}
+
+/// Reads games from the text of a PGN, one after the other.
+struct PgnReader<'a> {
+    rest: &'a str,
+}
+
+impl<'a> PgnReader<'a> {
+    /// Reads the next game, or `None` if there are no more.
+    fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        let mut headers = vec![];
+        loop {
+            self.skip_whitespace();
+            if !self.rest.starts_with('[') {
+                break;
+            }
+            headers.push(self.read_header()?);
+        }
+
+        self.skip_whitespace();
+        if headers.is_empty() && self.rest.is_empty() {
+            return Ok(None);
+        }
+
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(header, _)| header == name)
+                .map(|(_, value): &(String, String)| value.as_str())
+        };
+        let board = match header("FEN") {
+            Some(fen) => Fen::try_from(fen)?.board,
+            None => get_starting_board(),
+        };
+        let mut result = header("Result").and_then(parse_result);
+
+        let mut tree = GameTree::new(board, GameVariant::Standard);
+        let mut variations: Vec<NodeId> = vec![];
+        let mut starting_comments: Vec<String> = vec![];
+        let mut is_variation_start = false;
+        let mut movements = 0;
+        while let Some(token) = self.read_token()? {
+            match token {
+                Token::Comment(text) if is_variation_start => starting_comments.push(text),
+                Token::Comment(text) => tree.annotations_mut().add_comment(&text),
+                Token::Nag(nag) => tree.annotations_mut().nags.push(nag),
+                Token::Move(san) => {
+                    movements += 1;
+                    let movement = tree
+                        .board()
+                        .parse_san(&san)
+                        .map_err(|error| PgnError::InvalidMove(movements, error))?;
+                    tree.play(movement)
+                        .map_err(|error| PgnError::IllegalMove(movements, error))?;
+                    for comment in starting_comments.drain(..) {
+                        tree.annotations_mut().add_starting_comment(&comment);
+                    }
+                    is_variation_start = false;
+                }
+                Token::StartVariation => {
+                    let node = tree.cursor();
+                    if !tree.back() {
+                        return Err(PgnError::InvalidVariation);
+                    }
+                    variations.push(node);
+                    is_variation_start = true;
+                }
+                Token::EndVariation => {
+                    let node = variations.pop().ok_or(PgnError::InvalidVariation)?;
+                    tree.go_to(node);
+                    is_variation_start = false;
+                }
+                Token::Result(game_result) => {
+                    result = game_result;
+                    break;
+                }
+            }
+        }
+        if !variations.is_empty() {
+            return Err(PgnError::UnclosedVariation);
+        }
+
+        tree.to_start();
+        tree.to_end();
+
+        Ok(Some(PgnGame {
+            headers,
+            tree,
+            result,
+        }))
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            self.rest = self.rest.trim_start();
+            // Lines starting with `%` are escaped from the PGN.
+            if self.rest.starts_with('%') {
+                self.skip_line();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        self.rest = self.rest.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+
+    /// Reads a tag of the form `[Name "Value"]`.
+    fn read_header(&mut self) -> Result<(String, String), PgnError> {
+        let line = self.rest.lines().next().unwrap_or_default();
+        let invalid_header = || PgnError::InvalidHeader(line.to_string());
+
+        let text = self.rest[1..].trim_start();
+        let name_length = text
+            .find(|c: char| c.is_whitespace() || c == '"')
+            .ok_or_else(invalid_header)?;
+        let name = &text[..name_length];
+        let text = text[name_length..].trim_start();
+        let text = text.strip_prefix('"').ok_or_else(invalid_header)?;
+
+        let mut value = String::new();
+        let mut chars = text.char_indices();
+        let value_length = loop {
+            match chars.next() {
+                Some((_, '\\')) => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                Some((index, '"')) => break index,
+                Some((_, c)) => value.push(c),
+                None => return Err(invalid_header()),
+            }
+        };
+
+        let text = text[value_length + 1..].trim_start();
+        self.rest = text.strip_prefix(']').ok_or_else(invalid_header)?;
+
+        if name.is_empty() {
+            return Err(invalid_header());
+        }
+        Ok((name.to_string(), value))
+    }
+
+    /// Reads the next token of the movement text, or `None` if the game ended without a
+    /// result.
+    fn read_token(&mut self) -> Result<Option<Token>, PgnError> {
+        loop {
+            self.skip_whitespace();
+            let Some(first) = self.rest.chars().next() else {
+                return Ok(None);
+            };
+
+            let token = match first {
+                // A tag means a new game started.
+                '[' => return Ok(None),
+                '{' => {
+                    let (comment, rest) = self.rest[1..]
+                        .split_once('}')
+                        .ok_or(PgnError::UnclosedComment)?;
+                    self.rest = rest;
+                    Token::Comment(comment.trim().to_string())
+                }
+                ';' => {
+                    let comment = self.rest[1..].lines().next().unwrap_or_default();
+                    let comment = comment.trim().to_string();
+                    self.skip_line();
+                    Token::Comment(comment)
+                }
+                '(' => {
+                    self.rest = &self.rest[1..];
+                    Token::StartVariation
+                }
+                ')' => {
+                    self.rest = &self.rest[1..];
+                    Token::EndVariation
+                }
+                _ => {
+                    let mut length = self
+                        .rest
+                        .find(|c: char| c.is_whitespace() || "(){};[".contains(c))
+                        .unwrap_or(self.rest.len());
+                    // Symbols like `!?` after a movement are read after it as a glyph.
+                    let movement = self.rest[..length].trim_end_matches(['!', '?']);
+                    if !movement.is_empty()
+                        && nag_from_symbol(&self.rest[movement.len()..length]).is_some()
+                    {
+                        length = movement.len();
+                    }
+                    let word = &self.rest[..length];
+                    self.rest = &self.rest[length..];
+
+                    match read_word(word) {
+                        Some(token) => token,
+                        None => continue,
+                    }
+                }
+            };
+
+            return Ok(Some(token));
+        }
+    }
+}
+
+/// Get's the token of a word of the movement text, ignoring move numbers.
+fn read_word(word: &str) -> Option<Token> {
+    if let Some(nag) = word.strip_prefix('$') {
+        return nag.parse().ok().map(Token::Nag);
+    }
+    if let Some(nag) = nag_from_symbol(word) {
+        return Some(Token::Nag(nag));
+    }
+    if word == "*" || parse_result(word).is_some() {
+        return Some(Token::Result(parse_result(word)));
+    }
+
+    // Only digits followed by dots are a move number, since castling can be written as `0-0`.
+    let number_length = word.len() - word.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let word = match word[number_length..].strip_prefix('.') {
+        Some(movement) if number_length > 0 => movement.trim_start_matches('.'),
+        _ => word,
+    };
+    if word.is_empty() {
+        None
+    } else {
+        Some(Token::Move(word.to_string()))
+    }
+}
+
+fn parse_result(result: &str) -> Option<GameResult> {
+    match result {
+        "1-0" => Some(GameResult::WhiteWins),
+        "0-1" => Some(GameResult::BlackWins),
+        "1/2-1/2" => Some(GameResult::Draw),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNOTATED_GAME: &str = r#"[Event "Casual game"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "White"]
+[Black "Black"]
+[Result "1-0"]
+
+{ An open game } 1. e4 e5 2. Nf3 $1 { Attacking the pawn } 2... Nc6 (2... d6 3.
+d4 (3. Bc4 Be7) 3... Nf6) (2... Nf6 $2) 3. Bb5 a6 $6 4. Ba4 1-0
+"#;
+
+    fn mainline_san(game: &PgnGame) -> Vec<String> {
+        game.tree
+            .mainline()
+            .into_iter()
+            .filter_map(|id| {
+                game.tree
+                    .node(id)?
+                    .record()
+                    .map(|record| record.san.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_variations_nags_and_comments() {
+        let game = PgnGame::parse(ANNOTATED_GAME).unwrap();
+
+        assert_eq!(
+            mainline_san(&game),
+            ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4"]
+        );
+        assert_eq!(game.result, Some(GameResult::WhiteWins));
+        assert_eq!(game.header("White"), Some("White"));
+
+        let written = game.to_string();
+        assert_eq!(written, ANNOTATED_GAME);
+        assert_eq!(PgnGame::parse(&written).unwrap().to_string(), written);
+    }
+
+    #[test]
+    fn reads_castling_written_with_zeros() {
+        let game = PgnGame::parse(
+            "1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 d6 5. d3 Be6 6. Nc3 Qd7 7. Be3 0-0-0 *",
+        )
+        .unwrap();
+
+        let mainline = mainline_san(&game);
+        assert_eq!(mainline[6], "O-O");
+        assert_eq!(mainline[13], "O-O-O");
+
+        let written = game.to_string();
+        assert!(written.contains("4. O-O d6"));
+        assert!(written.contains("7. Be3 O-O-O"));
+        let read_game = PgnGame::parse(&written).unwrap();
+        assert_eq!(mainline_san(&read_game), mainline);
+        assert_eq!(read_game.to_string(), written);
+    }
+}
@@ -0,0 +1,437 @@
+use super::*;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// Defines how a kind of piece moves on the board.
+/// Directions and offsets are always written from the white player point of view, so a positive
+/// row goes forward for white and backwards for black.
+pub trait PieceDefinition: Sync {
+    /// Get's all the paths the given piece could take if the board of the given size were empty.
+    fn movement_paths(&self, piece: &ChessPiece, dimensions: &BoardDimensions)
+        -> Vec<MovementPath>;
+}
+
+/// A piece that jumps directly to the cells at the given offsets, like the knight or the king.
+pub struct Leaper {
+    pub offsets: &'static [(isize, isize)],
+    pub kind: MovementKind,
+}
+
+/// A piece that slides in the given directions until it finds another piece, like the rook or
+/// the bishop. If `max_distance` is set the piece can't slide farther than that.
+pub struct Rider {
+    pub directions: &'static [(isize, isize)],
+    pub max_distance: Option<usize>,
+    pub kind: MovementKind,
+}
+
+/// A piece that can move like any of the pieces it combines, like the queen or the amazon.
+pub struct Combination(pub &'static [&'static dyn PieceDefinition]);
+
+/// The pawn moves forward one cell, or two if it's on its starting position, and only captures
+/// diagonally. En passant is handled by the board because it depends on the previous movement.
+pub struct PawnDefinition;
+
+impl PieceDefinition for Leaper {
+    fn movement_paths(
+        &self,
+        piece: &ChessPiece,
+        dimensions: &BoardDimensions,
+    ) -> Vec<MovementPath> {
+        self.offsets
+            .iter()
+            .filter_map(|offset| relative_position(piece, dimensions, *offset, 1))
+            .map(|position| MovementPath {
+                path: BoardPath(vec![position]),
+                kind: self.kind,
+            })
+            .collect()
+    }
+}
+
+impl PieceDefinition for Rider {
+    fn movement_paths(
+        &self,
+        piece: &ChessPiece,
+        dimensions: &BoardDimensions,
+    ) -> Vec<MovementPath> {
+        self.directions
+            .iter()
+            .map(|direction| {
+                let path = (1..)
+                    .take_while(|distance| self.max_distance.map_or(true, |max| *distance <= max))
+                    .map_while(|distance| {
+                        relative_position(piece, dimensions, *direction, distance as isize)
+                    })
+                    .collect::<Vec<BoardPosition>>();
+                MovementPath {
+                    path: BoardPath(path),
+                    kind: self.kind,
+                }
+            })
+            .collect()
+    }
+}
+
+impl PieceDefinition for Combination {
+    fn movement_paths(
+        &self,
+        piece: &ChessPiece,
+        dimensions: &BoardDimensions,
+    ) -> Vec<MovementPath> {
+        self.0
+            .iter()
+            .flat_map(|definition| definition.movement_paths(piece, dimensions))
+            .collect()
+    }
+}
+
+impl PieceDefinition for PawnDefinition {
+    fn movement_paths(
+        &self,
+        piece: &ChessPiece,
+        dimensions: &BoardDimensions,
+    ) -> Vec<MovementPath> {
+        let forward = Rider {
+            directions: &[(1, 0)],
+            max_distance: Some(
+                if dimensions.allows_pawn_double_step()
+                    && is_pawn_on_starting_position(piece, dimensions)
+                {
+                    2
+                } else {
+                    1
+                },
+            ),
+            kind: MovementKind::MoveOnly,
+        };
+
+        let mut paths = forward.movement_paths(piece, dimensions);
+        paths.append(&mut PAWN_CAPTURES.movement_paths(piece, dimensions));
+        paths
+    }
+}
+
+/// Get's the position at the given offset from the piece, multiplied by the distance.
+/// The row offset is flipped for black pieces. Positions outside of the board are discarded.
+fn relative_position(
+    piece: &ChessPiece,
+    dimensions: &BoardDimensions,
+    (row_offset, column_offset): (isize, isize),
+    distance: isize,
+) -> Option<BoardPosition> {
+    let (row, column) = piece.position();
+    let row_offset = match piece.color() {
+        PieceColors::White => row_offset,
+        PieceColors::Black => -row_offset,
+    };
+
+    (
+        row as isize + row_offset * distance,
+        column as isize + column_offset * distance,
+    )
+        .try_into()
+        .ok()
+        .filter(|position| dimensions.contains(position))
+}
+
+const ORTHOGONAL_DIRECTIONS: &[(isize, isize)] = &[(1, 0), (0, 1), (-1, 0), (0, -1)];
+const DIAGONAL_DIRECTIONS: &[(isize, isize)] = &[(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+const PAWN_CAPTURES: Leaper = Leaper {
+    offsets: &[(1, -1), (1, 1)],
+    kind: MovementKind::CaptureOnly,
+};
+
+pub const PAWN: PawnDefinition = PawnDefinition;
+
+pub const KNIGHT: Leaper = Leaper {
+    offsets: &[
+        (2, 1),
+        (1, 2),
+        (2, -1),
+        (1, -2),
+        (-1, -2),
+        (-2, -1),
+        (-2, 1),
+        (-1, 2),
+    ],
+    kind: MovementKind::MoveAndCapture,
+};
+
+pub const BISHOP: Rider = Rider {
+    directions: DIAGONAL_DIRECTIONS,
+    max_distance: None,
+    kind: MovementKind::MoveAndCapture,
+};
+
+pub const ROOK: Rider = Rider {
+    directions: ORTHOGONAL_DIRECTIONS,
+    max_distance: None,
+    kind: MovementKind::MoveAndCapture,
+};
+
+pub const QUEEN: Combination = Combination(&[&ROOK, &BISHOP]);
+
+pub const KING: Leaper = Leaper {
+    offsets: &[
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, -1),
+        (0, 1),
+        (-1, -1),
+        (-1, 1),
+        (-1, 0),
+    ],
+    kind: MovementKind::MoveAndCapture,
+};
+
+/// Moves like a bishop or a knight.
+pub const ARCHBISHOP: Combination = Combination(&[&BISHOP, &KNIGHT]);
+
+/// Moves like a rook or a knight.
+pub const CHANCELLOR: Combination = Combination(&[&ROOK, &KNIGHT]);
+
+/// Moves like a queen or a knight.
+pub const AMAZON: Combination = Combination(&[&QUEEN, &KNIGHT]);
+
+/// The definition of the fairy pieces that are not on the board, which can't move.
+const UNKNOWN_PIECE: Combination = Combination(&[]);
+
+/// A piece that's not on standard chess, written with its own letter.
+/// Boards only know how the fairy pieces they were given move, see `Board::fairy_pieces`.
+#[derive(Clone, Copy)]
+pub struct FairyPiece {
+    name: &'static str,
+    symbol: char,
+    value: i32,
+    definition: &'static dyn PieceDefinition,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FairyPieceError {
+    #[error("The symbol `{0}` of a fairy piece must be an ASCII letter.")]
+    InvalidSymbol(char),
+    #[error("The symbol `{0}` is already used by a standard piece.")]
+    SymbolTaken(char),
+    #[error("There's no fairy piece written as `{0}`.")]
+    UnknownSymbol(char),
+}
+
+impl FairyPiece {
+    pub const ARCHBISHOP: FairyPiece = FairyPiece {
+        name: "Archbishop",
+        symbol: 'A',
+        value: 850,
+        definition: &ARCHBISHOP,
+    };
+    pub const CHANCELLOR: FairyPiece = FairyPiece {
+        name: "Chancellor",
+        symbol: 'C',
+        value: 900,
+        definition: &CHANCELLOR,
+    };
+    pub const AMAZON: FairyPiece = FairyPiece {
+        name: "Amazon",
+        symbol: 'M',
+        value: 1250,
+        definition: &AMAZON,
+    };
+
+    /// The fairy pieces the engine defines, the only ones boards can be deserialized with.
+    pub const BUILT_IN: [FairyPiece; 3] = [
+        FairyPiece::ARCHBISHOP,
+        FairyPiece::CHANCELLOR,
+        FairyPiece::AMAZON,
+    ];
+
+    /// Creates a fairy piece written with the given letter and worth `value` centipawns.
+    /// Returns an error if the symbol is not a letter or a standard piece already uses it.
+    pub fn new(
+        name: &'static str,
+        symbol: char,
+        value: i32,
+        definition: &'static dyn PieceDefinition,
+    ) -> Result<Self, FairyPieceError> {
+        let uppercase_symbol = symbol.to_ascii_uppercase();
+        if !uppercase_symbol.is_ascii_alphabetic() {
+            return Err(FairyPieceError::InvalidSymbol(symbol));
+        }
+        if PieceTypes::from_symbol(uppercase_symbol).is_some() {
+            return Err(FairyPieceError::SymbolTaken(uppercase_symbol));
+        }
+
+        Ok(FairyPiece {
+            name,
+            symbol: uppercase_symbol,
+            value,
+            definition,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get's the letter used to write the piece, in uppercase.
+    pub fn symbol(&self) -> char {
+        self.symbol
+    }
+
+    /// Get's how many centipawns the piece is worth.
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn definition(&self) -> &'static dyn PieceDefinition {
+        self.definition
+    }
+
+    /// Get's the kind of the pieces of this fairy piece on a board.
+    pub fn kind(&self) -> PieceTypes {
+        PieceTypes::Fairy(self.symbol)
+    }
+}
+
+impl std::fmt::Debug for FairyPiece {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("FairyPiece")
+            .field("name", &self.name)
+            .field("symbol", &self.symbol)
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Fairy pieces are the same if they are written with the same letter.
+impl PartialEq for FairyPiece {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl Eq for FairyPiece {}
+
+/// Fairy pieces are serialized as their letter, because their definition is code.
+impl Serialize for FairyPiece {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.symbol)
+    }
+}
+
+/// Only the built in fairy pieces can be deserialized.
+impl<'de> Deserialize<'de> for FairyPiece {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let symbol = char::deserialize(deserializer)?;
+        FairyPiece::BUILT_IN
+            .into_iter()
+            .find(|piece| piece.symbol == symbol)
+            .ok_or_else(|| de::Error::custom(FairyPieceError::UnknownSymbol(symbol)))
+    }
+}
+
+impl Board {
+    /// Get's the fairy piece of this board written with the given letter, ignoring its case.
+    pub fn fairy_piece(&self, symbol: char) -> Option<&FairyPiece> {
+        let symbol = symbol.to_ascii_uppercase();
+        self.fairy_pieces
+            .iter()
+            .find(|piece| piece.symbol == symbol)
+    }
+
+    /// Get's the kind of piece written with the given letter on this board, ignoring its case.
+    pub fn piece_kind(&self, symbol: char) -> Option<PieceTypes> {
+        PieceTypes::from_symbol(symbol).or_else(|| self.fairy_piece(symbol).map(FairyPiece::kind))
+    }
+
+    /// Get's the definition of how the given kind of piece moves on this board.
+    /// Fairy pieces that are not on the fairy pieces of the board can't move.
+    pub fn piece_definition(&self, kind: &PieceTypes) -> &'static dyn PieceDefinition {
+        match kind {
+            PieceTypes::Pawn => &PAWN,
+            PieceTypes::Rook => &ROOK,
+            PieceTypes::Knight => &KNIGHT,
+            PieceTypes::Bishop => &BISHOP,
+            PieceTypes::Queen => &QUEEN,
+            PieceTypes::King => &KING,
+            PieceTypes::Fairy(symbol) => self
+                .fairy_piece(*symbol)
+                .map_or(&UNKNOWN_PIECE, FairyPiece::definition),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZEBRA: Leaper = Leaper {
+        offsets: &[
+            (3, 2),
+            (2, 3),
+            (-2, 3),
+            (-3, 2),
+            (-3, -2),
+            (-2, -3),
+            (2, -3),
+            (3, -2),
+        ],
+        kind: MovementKind::MoveAndCapture,
+    };
+
+    /// Get's the starting board with a white zebra on d4.
+    fn board_with_zebra() -> (Board, ChessPiece) {
+        let mut board = get_starting_board();
+        let position: BoardPosition = "d4".try_into().unwrap();
+        let zebra = ChessPiece::new(PieceTypes::Fairy('Z'), position.clone(), PieceColors::White);
+        let (row, column) = position.into();
+        board.cells[row][column] = ChessCell::some(zebra.clone());
+        board.white_pieces.push(zebra.clone());
+        (board, zebra)
+    }
+
+    #[test]
+    fn fairy_pieces_of_the_board_can_move() {
+        let (mut board, zebra) = board_with_zebra();
+        assert!(get_valid_movements_positions(&zebra, &board).is_empty());
+
+        board
+            .fairy_pieces
+            .push(FairyPiece::new("Zebra", 'z', 250, &ZEBRA).unwrap());
+        let positions = get_valid_movements_positions(&zebra, &board);
+
+        assert_eq!(positions.len(), 4);
+        for position in ["b7", "f7", "a6", "g6"] {
+            assert!(positions.contains(&position.try_into().unwrap()));
+        }
+    }
+
+    #[test]
+    fn fairy_pieces_need_a_letter_of_their_own() {
+        assert_eq!(
+            FairyPiece::new("Zebra", 'z', 250, &ZEBRA).map(|piece| piece.kind()),
+            Ok(PieceTypes::Fairy('Z'))
+        );
+        assert_eq!(
+            FairyPiece::new("Zebra", 'n', 250, &ZEBRA),
+            Err(FairyPieceError::SymbolTaken('N'))
+        );
+        assert_eq!(
+            FairyPiece::new("Zebra", '?', 250, &ZEBRA),
+            Err(FairyPieceError::InvalidSymbol('?'))
+        );
+    }
+
+    #[test]
+    fn only_built_in_fairy_pieces_are_deserialized() {
+        let json = serde_json::to_string(&FairyPiece::BUILT_IN).unwrap();
+        assert_eq!(json, r#"["A","C","M"]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<FairyPiece>>(&json).unwrap(),
+            FairyPiece::BUILT_IN
+        );
+        assert!(serde_json::from_str::<FairyPiece>(r#""Z""#).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use super::*;
+
+use thiserror::Error;
+
+/// Get's the Standard Algebraic Notation of a movement made on the given board, like `Nbd2`,
+/// `exd6`, `e8=Q+` or `O-O-O#`.
+pub(crate) fn get_san(
+    board: &Board,
+    piece: &ChessPiece,
+    destination: &BoardPosition,
+    applied_movement: &AppliedMovement,
+    is_checkmate: bool,
+) -> String {
+    let mut san = match applied_movement.castling {
+        Some(ChessBoardDirections::Left) => String::from("O-O-O"),
+        Some(_) => String::from("O-O"),
+        None => {
+            let is_capture = applied_movement.captured_piece.is_some();
+            let mut san = String::new();
+
+            if piece.kind() == &PieceTypes::Pawn {
+                if is_capture {
+                    san.push_str(&piece.board_position().column.to_string());
+                }
+            } else {
+                san.push(piece.kind().symbol());
+                san.push_str(&get_disambiguation(board, piece, destination));
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&destination.to_string());
+
+            if let Some(promotion) = applied_movement.promotion {
+                san.push('=');
+                san.push(promotion.symbol());
+            }
+            san
+        }
+    };
+
+    if is_checkmate {
+        san.push('#');
+    } else if applied_movement
+        .board
+        .is_in_check(&piece.color().opponent())
+    {
+        san.push('+');
+    }
+
+    san
+}
+
+/// Get's the part of the origin needed to tell the piece apart from other pieces of the same kind
+/// that could move to the same destination.
+fn get_disambiguation(board: &Board, piece: &ChessPiece, destination: &BoardPosition) -> String {
+    let origin = piece.board_position();
+    let rivals: Vec<&ChessPiece> = board
+        .get_pieces_from(piece.color())
+        .iter()
+        .filter(|other| other.kind() == piece.kind() && other.board_position() != origin)
+        .filter(|other| apply_movement(board, other, destination, None).is_ok())
+        .collect();
+
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals
+        .iter()
+        .all(|rival| rival.board_position().column != origin.column)
+    {
+        origin.column.to_string()
+    } else if rivals
+        .iter()
+        .all(|rival| rival.board_position().row != origin.row)
+    {
+        origin.row.to_string()
+    } else {
+        origin.to_string()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SanError {
+    #[error("`{0}` is not written in Standard Algebraic Notation.")]
+    InvalidFormat(String),
+    #[error("The movement `{0}` can't be played on this board.")]
+    IllegalMove(String),
+    #[error("The movement `{0}` could be played by more than one piece.")]
+    AmbiguousMove(String),
+}
+
+impl Board {
+    /// Get's the movement written in Standard Algebraic Notation for the player that moves next.
+    /// Check and annotation suffixes are ignored, and origins more precise than needed, like
+    /// `Ng1f3`, are accepted.
+    pub fn parse_san(&self, san: &str) -> Result<BoardMovement, SanError> {
+        let invalid_format = || SanError::InvalidFormat(san.to_string());
+        let text = san.trim_end_matches(['+', '#', '!', '?']);
+
+        let castling = match text {
+            "O-O" | "0-0" => Some(ChessBoardDirections::Right),
+            "O-O-O" | "0-0-0" => Some(ChessBoardDirections::Left),
+            _ => None,
+        };
+        if let Some(direction) = castling {
+            let king_position = self.get_king_position(&self.side_to_move);
+            let (king_file, _) = castling_files(&direction, &self.dimensions());
+            let destination = BoardPosition::try_from((king_position.row.to_index(), king_file))
+                .map_err(|_| invalid_format())?;
+            let king = self
+                .get_piece(&king_position)
+                .filter(|king| get_valid_movements_positions(king, self).contains(&destination))
+                .ok_or_else(|| SanError::IllegalMove(san.to_string()))?;
+            return Ok(BoardMovement {
+                piece: king,
+                destination,
+                promotion: None,
+            });
+        }
+
+        let (text, promotion) = match text.split_once('=') {
+            Some((text, promotion)) => {
+                let mut symbols = promotion.chars();
+                let kind = symbols
+                    .next()
+                    .and_then(|symbol| self.piece_kind(symbol))
+                    .filter(|_| symbols.next().is_none())
+                    .ok_or_else(invalid_format)?;
+                (text, Some(kind))
+            }
+            None => match text.chars().last() {
+                Some(symbol) if symbol.is_ascii_uppercase() => (
+                    &text[..text.len() - 1],
+                    Some(self.piece_kind(symbol).ok_or_else(invalid_format)?),
+                ),
+                _ => (text, None),
+            },
+        };
+
+        let (kind, text) = match text.chars().next() {
+            Some(symbol) if symbol.is_ascii_uppercase() => (
+                self.piece_kind(symbol).ok_or_else(invalid_format)?,
+                &text[1..],
+            ),
+            _ => (PieceTypes::Pawn, text),
+        };
+
+        let coordinates: String = text.chars().filter(|c| !matches!(c, 'x' | '-')).collect();
+        let file_index = coordinates
+            .rfind(|c: char| c.is_ascii_lowercase())
+            .ok_or_else(invalid_format)?;
+        let destination =
+            BoardPosition::try_from(&coordinates[file_index..]).map_err(|_| invalid_format())?;
+        let origin = &coordinates[..file_index];
+        let origin_file = origin.chars().find(char::is_ascii_lowercase);
+        let origin_rank: String = origin.chars().filter(char::is_ascii_digit).collect();
+        if origin.len() != origin_file.map_or(0, |_| 1) + origin_rank.len() {
+            return Err(invalid_format());
+        }
+
+        let candidates: Vec<&ChessPiece> = self
+            .get_pieces_from(&self.side_to_move)
+            .iter()
+            .filter(|piece| piece.kind() == &kind)
+            .filter(|piece| {
+                origin_file.map_or(true, |file| {
+                    piece.board_position().column.to_string() == file.to_string()
+                })
+            })
+            .filter(|piece| {
+                origin_rank.is_empty() || piece.board_position().row.to_string() == origin_rank
+            })
+            .filter(|piece| get_valid_movements_positions(piece, self).contains(&destination))
+            .collect();
+
+        match candidates.as_slice() {
+            [piece] => Ok(BoardMovement {
+                piece: (*piece).clone(),
+                destination,
+                promotion,
+            }),
+            [] => Err(SanError::IllegalMove(san.to_string())),
+            _ => Err(SanError::AmbiguousMove(san.to_string())),
+        }
+    }
+}
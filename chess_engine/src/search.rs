@@ -0,0 +1,810 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// The depth the search stops at when no limits are given.
+pub const DEFAULT_SEARCH_DEPTH: usize = 4;
+/// The deepest the search can go.
+pub const MAX_SEARCH_DEPTH: usize = 64;
+/// The score of being checkmated right now. Mates further away score a bit less.
+const MATE_SCORE: i32 = 1_000_000;
+const INFINITY: i32 = MATE_SCORE + 1;
+/// The score of a position the tablebase says is won. Wins further away score a bit less.
+const TABLEBASE_WIN_SCORE: i32 = 20_000;
+/// The most threads a search can use.
+pub const MAX_SEARCH_THREADS: usize = 256;
+/// The most lines a search can look for at once.
+pub const MAX_MULTI_PV: usize = 256;
+/// How many nodes are searched between each look at the clock.
+const NODES_BETWEEN_TIME_CHECKS: u64 = 256;
+
+/// When the search should stop. The search stops at the first limit reached, but it always
+/// completes at least a depth of one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchLimits {
+    pub depth: Option<usize>,
+    pub nodes: Option<u64>,
+    /// The time the search can take. It can't be used on web assembly, where there's no clock.
+    pub movetime: Option<Duration>,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        SearchLimits {
+            depth: Some(DEFAULT_SEARCH_DEPTH),
+            nodes: None,
+            movetime: None,
+        }
+    }
+}
+
+/// The best line found by a search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// The best movement found, or `None` if the player can't move.
+    pub best_move: Option<BoardMovement>,
+    /// How good the position is for the player that moves next.
+    pub score: Evaluation,
+    pub depth: usize,
+    /// The movements both players are expected to make, starting with the best one.
+    pub principal_variation: Vec<BoardMovement>,
+    /// The best lines found, each starting with a different movement, from the best to the
+    /// worst. The first one is the principal variation.
+    pub lines: Vec<SearchLine>,
+    /// The amount of positions looked at.
+    pub nodes: u64,
+    /// The amount of positions found on the tablebase.
+    pub tablebase_hits: u64,
+}
+
+/// One of the lines found by a search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchLine {
+    /// How good the position is for the player that moves next if it plays this line.
+    pub score: Evaluation,
+    pub movements: Vec<BoardMovement>,
+}
+
+/// A search for the best movement of a position, that goes one depth deeper each time.
+#[derive(Debug, Clone)]
+pub struct Search {
+    board: Board,
+    limits: SearchLimits,
+    /// How many lines, each starting with a different movement, are searched.
+    multi_pv: usize,
+    deadline: Option<Instant>,
+    /// Decides when to stop from the time left on the clock, counting from `started_at`.
+    time_manager: Option<TimeManager>,
+    started_at: Option<Instant>,
+    /// How often the best movement changed between depths, with the older changes counting
+    /// less.
+    best_move_changes: f64,
+    /// The value of the deepest completed depth.
+    last_value: Option<i32>,
+    /// A flag another thread can raise to stop the search.
+    stop_signal: Option<Arc<AtomicBool>>,
+    /// The hashes of the positions before the searched one and on the line being searched, to
+    /// spot repetitions.
+    history: Vec<u64>,
+    depth: usize,
+    nodes: u64,
+    tablebase: Option<Arc<dyn EndgameTablebase>>,
+    /// The movements that keep the result the tablebase knows for the searched position.
+    tablebase_movements: Option<Vec<BoardMovement>>,
+    tablebase_hits: u64,
+    transposition_table: Arc<TranspositionTable>,
+    threads: usize,
+    /// The threads helping the search, once they are started.
+    helpers: Option<Arc<HelperThreads>>,
+    result: Option<SearchResult>,
+    /// The depth being searched, if it was paused before searching every movement.
+    root: Option<RootSearch>,
+    is_finished: bool,
+}
+
+/// The progress of a depth, so it can be searched a few movements at a time.
+#[derive(Debug, Clone)]
+struct RootSearch {
+    movements: Vec<BoardMovement>,
+    next_movement: usize,
+    has_legal_movements: bool,
+    /// The values and lines of the best movements searched so far, from the best to the worst.
+    lines: Vec<(i32, Vec<BoardMovement>)>,
+}
+
+/// The flag that stops the threads helping a search. It's raised when the search finishes or
+/// is dropped.
+#[derive(Debug)]
+struct HelperThreads {
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl HelperThreads {
+    fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for HelperThreads {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl RootSearch {
+    /// Get's the value a movement must beat to be one of the best `multi_pv` lines.
+    fn alpha(&self, multi_pv: usize) -> i32 {
+        match self.lines.get(multi_pv - 1) {
+            Some((value, _)) => *value,
+            None => -INFINITY,
+        }
+    }
+}
+
+/// Searches the best movement for the player that moves next on the board.
+/// Returns `None` if the search couldn't complete a single depth.
+pub fn search_best_move(board: &Board, limits: SearchLimits) -> Option<SearchResult> {
+    Search::new(board.clone(), limits).run()
+}
+
+impl Search {
+    pub fn new(board: Board, limits: SearchLimits) -> Self {
+        let deadline = limits.movetime.map(|movetime| Instant::now() + movetime);
+        Search {
+            board,
+            limits,
+            multi_pv: 1,
+            deadline,
+            time_manager: None,
+            started_at: None,
+            best_move_changes: 0.0,
+            last_value: None,
+            stop_signal: None,
+            history: vec![],
+            depth: 0,
+            nodes: 0,
+            tablebase: None,
+            tablebase_movements: None,
+            tablebase_hits: 0,
+            transposition_table: Arc::new(TranspositionTable::default()),
+            threads: 1,
+            helpers: None,
+            result: None,
+            root: None,
+            is_finished: false,
+        }
+    }
+
+    /// Sets the hashes of the positions played before the searched one, so the search avoids or
+    /// looks for draws by repetition.
+    pub fn with_history(mut self, history: Vec<u64>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Sets how many lines, each starting with a different movement, the search looks for.
+    /// Looking for more lines than one makes the search slower, because the alternatives can't
+    /// be cut off as soon.
+    pub fn with_multi_pv(mut self, lines: usize) -> Self {
+        self.multi_pv = lines.clamp(1, MAX_MULTI_PV);
+        self
+    }
+
+    /// Sets the time manager that decides how long to search from the time left on the clock.
+    /// The search is stopped at its hard limit, and no depth is started near its soft limit,
+    /// which is extended while the best movement keeps changing or the score drops.
+    /// The time starts counting now. It can't be used on web assembly, where there's no clock.
+    pub fn with_time_manager(mut self, time_manager: TimeManager) -> Self {
+        let now = Instant::now();
+        let hard_deadline = now + time_manager.hard_limit();
+        self.deadline = Some(
+            self.deadline
+                .map_or(hard_deadline, |deadline| deadline.min(hard_deadline)),
+        );
+        self.time_manager = Some(time_manager);
+        self.started_at = Some(now);
+        self
+    }
+
+    /// Sets the transposition table, so it can be kept between the searches of a game.
+    pub fn with_transposition_table(
+        mut self,
+        transposition_table: Arc<TranspositionTable>,
+    ) -> Self {
+        self.transposition_table = transposition_table;
+        self
+    }
+
+    /// Sets how many threads search at once. The extra threads search the same position
+    /// sharing the transposition table, filling it with results the main thread can use, and
+    /// stop when the main one finishes. Only the main thread counts for the nodes limit and the
+    /// reported nodes. It can't be used on web assembly, where there are no threads.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.clamp(1, MAX_SEARCH_THREADS);
+        self
+    }
+
+    /// Sets a flag that stops the search when it's raised, so it can be stopped from another
+    /// thread. As with the other limits, the first depth is always completed.
+    pub fn with_stop_signal(mut self, stop_signal: Arc<AtomicBool>) -> Self {
+        self.stop_signal = Some(stop_signal);
+        self
+    }
+
+    /// Sets the endgame tablebase to look up the positions with few pieces on.
+    /// If the searched position is on it, only the movements that keep its result are searched.
+    pub fn with_tablebase(mut self, tablebase: Arc<dyn EndgameTablebase>) -> Self {
+        self.tablebase_movements = get_tablebase_movements(tablebase.as_ref(), &self.board);
+        self.tablebase = Some(tablebase);
+        self
+    }
+
+    /// Get's the result of the deepest completed depth.
+    pub fn result(&self) -> Option<&SearchResult> {
+        self.result.as_ref()
+    }
+
+    /// Checks if the search reached one of its limits, found a mate or the player can't move.
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Searches until one of the limits is reached, returning the best line found.
+    pub fn run(mut self) -> Option<SearchResult> {
+        while self.search_next_depth().is_some() {}
+        self.result
+    }
+
+    /// Searches one depth deeper than before, or the rest of the depth a step was searching.
+    /// Returns `None`, keeping the result of the previous depth, if the search is finished or a
+    /// limit was reached before completing the depth.
+    pub fn search_next_depth(&mut self) -> Option<&SearchResult> {
+        self.step(u64::MAX)
+    }
+
+    /// Searches the movements of the current depth until about `nodes` positions are looked at,
+    /// so the search can be done in small pieces. At least one movement is searched each step.
+    /// Returns the result of the depth if it was completed during the step, and `None` if it
+    /// wasn't or the search is finished.
+    pub fn step(&mut self, nodes: u64) -> Option<&SearchResult> {
+        if self.is_finished {
+            return None;
+        }
+        if self.threads > 1 && self.helpers.is_none() {
+            self.start_helpers();
+        }
+
+        let step_end = self.nodes.saturating_add(nodes);
+        let mut root = match self.root.take() {
+            Some(root) => root,
+            None => self.start_depth(),
+        };
+        while let Some(movement) = root.movements.get(root.next_movement).cloned() {
+            root.next_movement += 1;
+            if self.search_root_movement(&mut root, movement).is_none() {
+                self.finish();
+                return None;
+            }
+
+            if self.nodes >= step_end && root.next_movement < root.movements.len() {
+                self.root = Some(root);
+                return None;
+            }
+        }
+
+        self.finish_depth(root)
+    }
+
+    /// Stops the search, keeping the result of the deepest completed depth.
+    pub fn stop(&mut self) {
+        self.root = None;
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        self.is_finished = true;
+        if let Some(helpers) = &self.helpers {
+            helpers.stop();
+        }
+    }
+
+    /// Starts the threads that help the search. Half of them skip the first depth, so the
+    /// threads don't all search the same depth at the same time.
+    fn start_helpers(&mut self) {
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        for index in 1..self.threads {
+            let mut helper = self.clone();
+            helper.limits.nodes = None;
+            helper.deadline = None;
+            helper.time_manager = None;
+            helper.stop_signal = Some(stop_signal.clone());
+            helper.threads = 1;
+            helper.depth = index % 2;
+            std::thread::spawn(move || while helper.search_next_depth().is_some() {});
+        }
+
+        self.helpers = Some(Arc::new(HelperThreads { stop_signal }));
+    }
+
+    fn start_depth(&mut self) -> RootSearch {
+        self.depth += 1;
+        self.nodes += 1;
+
+        let mut movements = match &self.tablebase_movements {
+            Some(movements) => movements.clone(),
+            None => get_candidate_movements(&self.board, &self.board.side_to_move),
+        };
+        let previous_best = self
+            .result
+            .as_ref()
+            .and_then(|result| result.principal_variation.first());
+        order_movements(&self.board, &mut movements, previous_best);
+
+        RootSearch {
+            movements,
+            next_movement: 0,
+            has_legal_movements: false,
+            lines: vec![],
+        }
+    }
+
+    /// Searches one of the movements of the board being searched.
+    /// Returns `None` if a limit was reached.
+    fn search_root_movement(
+        &mut self,
+        root: &mut RootSearch,
+        movement: BoardMovement,
+    ) -> Option<()> {
+        let Ok(applied_movement) = apply_movement(
+            &self.board,
+            &movement.piece,
+            &movement.destination,
+            movement.promotion,
+        ) else {
+            return Some(());
+        };
+        root.has_legal_movements = true;
+
+        let alpha = root.alpha(self.multi_pv);
+        let mut child_variation = vec![];
+        self.history.push(self.board.zobrist_hash());
+        let value = self.negamax(
+            &applied_movement.board,
+            self.depth - 1,
+            1,
+            -INFINITY,
+            -alpha,
+            &mut child_variation,
+        );
+        self.history.pop();
+
+        let value = -value?;
+        if value > alpha {
+            let mut line = vec![movement];
+            line.append(&mut child_variation);
+            let index = root.lines.partition_point(|(other, _)| *other >= value);
+            root.lines.insert(index, (value, line));
+            root.lines.truncate(self.multi_pv);
+        }
+
+        Some(())
+    }
+
+    fn finish_depth(&mut self, root: RootSearch) -> Option<&SearchResult> {
+        let value = if let Some((value, _)) = root.lines.first() {
+            *value
+        } else if root.has_legal_movements {
+            -INFINITY
+        } else if self.board.is_in_check(&self.board.side_to_move) {
+            -MATE_SCORE
+        } else {
+            0
+        };
+
+        let score = score_from_value(value);
+        let lines: Vec<SearchLine> = root
+            .lines
+            .into_iter()
+            .map(|(value, movements)| SearchLine {
+                score: score_from_value(value),
+                movements,
+            })
+            .collect();
+        let principal_variation = lines
+            .first()
+            .map(|line| line.movements.clone())
+            .unwrap_or_default();
+        let best_move_changed = self.result.as_ref().map_or(false, |result| {
+            result.best_move.as_ref() != principal_variation.first()
+        });
+        self.best_move_changes =
+            self.best_move_changes / 2.0 + if best_move_changed { 1.0 } else { 0.0 };
+        let score_drop = self
+            .last_value
+            .map_or(0, |last_value| last_value.saturating_sub(value));
+        self.last_value = Some(value);
+
+        let max_depth = self.limits.depth.unwrap_or(MAX_SEARCH_DEPTH);
+        let is_finished = self.depth >= max_depth.min(MAX_SEARCH_DEPTH)
+            || principal_variation.is_empty()
+            || matches!(score, Evaluation::Mate(_))
+            || self.is_out_of_soft_time(score_drop);
+        if is_finished {
+            self.finish();
+        }
+
+        self.result = Some(SearchResult {
+            best_move: principal_variation.first().cloned(),
+            score,
+            depth: self.depth,
+            principal_variation,
+            lines,
+            nodes: self.nodes,
+            tablebase_hits: self.tablebase_hits,
+        });
+        self.result.as_ref()
+    }
+
+    /// Checks if the time manager says there's no time to start another depth.
+    /// A depth usually takes longer than all the previous ones together, so no depth is started
+    /// once half of the soft limit is gone, instead of being thrown away at the hard limit.
+    fn is_out_of_soft_time(&self, score_drop: i32) -> bool {
+        let (Some(time_manager), Some(started_at)) = (&self.time_manager, self.started_at) else {
+            return false;
+        };
+
+        let soft_limit = time_manager.extended_soft_limit(self.best_move_changes, score_drop);
+        started_at.elapsed() * 2 >= soft_limit
+    }
+
+    /// Checks if a limit was reached. The first depth is always completed.
+    fn should_stop(&self) -> bool {
+        if self.depth <= 1 {
+            return false;
+        }
+
+        let out_of_nodes = self.limits.nodes.map_or(false, |nodes| self.nodes >= nodes);
+        let out_of_time = self.nodes % NODES_BETWEEN_TIME_CHECKS == 0
+            && self
+                .deadline
+                .map_or(false, |deadline| Instant::now() >= deadline);
+        let is_stopped = self
+            .stop_signal
+            .as_ref()
+            .map_or(false, |stop_signal| stop_signal.load(Ordering::Relaxed));
+        out_of_nodes || out_of_time || is_stopped
+    }
+
+    /// Get's the value of the position for the player that moves next, looking `depth`
+    /// movements ahead, and the line that leads to it.
+    /// Returns `None` if a limit was reached.
+    fn negamax(
+        &mut self,
+        board: &Board,
+        depth: usize,
+        ply: usize,
+        alpha: i32,
+        beta: i32,
+        principal_variation: &mut Vec<BoardMovement>,
+    ) -> Option<i32> {
+        principal_variation.clear();
+        if self.should_stop() {
+            return None;
+        }
+        if depth == 0 {
+            return self.quiescence(board, alpha, beta);
+        }
+        self.nodes += 1;
+
+        let hash = board.zobrist_hash();
+        if ply > 0 && self.history.contains(&hash) {
+            return Some(0);
+        }
+        if let Some(value) = self.probe_tablebase(board, ply) {
+            return Some(value);
+        }
+
+        let entry = self.transposition_table.probe(hash);
+        if let Some(entry) = entry.as_ref().filter(|entry| entry.depth >= depth) {
+            let value = value_from_table(entry.value, ply);
+            let is_cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => value >= beta,
+                Bound::Upper => value <= alpha,
+            };
+            if is_cutoff {
+                if entry.bound == Bound::Exact {
+                    *principal_variation = self.get_table_variation(board, depth);
+                }
+                return Some(value);
+            }
+        }
+
+        self.history.push(hash);
+        let value = self.search_movements(
+            board,
+            depth,
+            ply,
+            alpha,
+            beta,
+            entry.and_then(|entry| entry.movement),
+            principal_variation,
+        );
+        self.history.pop();
+        value
+    }
+
+    /// Get's the line the transposition table remembers from the position, following the best
+    /// movement of each entry for at most `depth` movements.
+    fn get_table_variation(&self, board: &Board, depth: usize) -> Vec<BoardMovement> {
+        let mut variation = vec![];
+        let mut board = board.clone();
+        while variation.len() < depth {
+            let Some(movement) = self
+                .transposition_table
+                .probe(board.zobrist_hash())
+                .and_then(|entry| entry.movement)
+                .and_then(|movement| find_table_movement(&board, movement))
+            else {
+                break;
+            };
+            let Ok(applied_movement) = apply_movement(
+                &board,
+                &movement.piece,
+                &movement.destination,
+                movement.promotion,
+            ) else {
+                break;
+            };
+
+            board = applied_movement.board;
+            variation.push(movement);
+        }
+
+        variation
+    }
+
+    /// Get's the value of the position from the tablebase, if it's on it.
+    /// Wins and losses the fifty move rule turns into draws are valued as draws.
+    fn probe_tablebase(&mut self, board: &Board, ply: usize) -> Option<i32> {
+        let tablebase = self.tablebase.as_ref()?;
+        if !is_in_tablebase(tablebase.as_ref(), board) {
+            return None;
+        }
+
+        let wdl = tablebase.probe_wdl(board)?;
+        self.tablebase_hits += 1;
+        Some(match wdl {
+            Wdl::Win => TABLEBASE_WIN_SCORE - ply as i32,
+            Wdl::Loss => -(TABLEBASE_WIN_SCORE - ply as i32),
+            _ => 0,
+        })
+    }
+
+    /// Searches every movement of the position, storing the result on the transposition table.
+    /// The movement the table remembers as the best, if any, is searched first.
+    #[allow(clippy::too_many_arguments)]
+    fn search_movements(
+        &mut self,
+        board: &Board,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        table_movement: Option<TableMovement>,
+        principal_variation: &mut Vec<BoardMovement>,
+    ) -> Option<i32> {
+        let color = board.side_to_move.clone();
+        let mut movements = get_candidate_movements(board, &color);
+        let table_movement = table_movement.and_then(|table_movement| {
+            movements
+                .iter()
+                .find(|movement| is_table_movement(movement, &table_movement))
+                .cloned()
+        });
+        let previous_best = table_movement.as_ref().or_else(|| {
+            self.result
+                .as_ref()
+                .and_then(|result| result.principal_variation.get(ply))
+        });
+        order_movements(board, &mut movements, previous_best);
+
+        let original_alpha = alpha;
+        let mut best_movement = None;
+        let mut best_value = -INFINITY;
+        let mut has_legal_movements = false;
+        let mut child_variation = vec![];
+        for movement in movements {
+            let Ok(applied_movement) = apply_movement(
+                board,
+                &movement.piece,
+                &movement.destination,
+                movement.promotion,
+            ) else {
+                continue;
+            };
+            has_legal_movements = true;
+
+            let value = -self.negamax(
+                &applied_movement.board,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                &mut child_variation,
+            )?;
+            if value > best_value {
+                best_value = value;
+                best_movement = Some(movement.clone());
+            }
+            if value > alpha {
+                alpha = value;
+                principal_variation.clear();
+                principal_variation.push(movement);
+                principal_variation.append(&mut child_variation);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if !has_legal_movements {
+            return Some(if board.is_in_check(&color) {
+                -(MATE_SCORE - ply as i32)
+            } else {
+                0
+            });
+        }
+
+        let bound = if best_value >= beta {
+            Bound::Lower
+        } else if best_value > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.transposition_table.store(
+            board.zobrist_hash(),
+            TableEntry {
+                value: value_to_table(best_value, ply),
+                depth,
+                bound,
+                movement: best_movement.map(|movement| {
+                    (
+                        movement.piece.board_position().clone(),
+                        movement.destination,
+                        movement.promotion,
+                    )
+                }),
+            },
+        );
+
+        Some(best_value)
+    }
+
+    /// Get's the value of the position once there are no more captures to make, so the search
+    /// doesn't stop in the middle of an exchange.
+    fn quiescence(&mut self, board: &Board, mut alpha: i32, beta: i32) -> Option<i32> {
+        if self.should_stop() {
+            return None;
+        }
+        self.nodes += 1;
+
+        let stand_pat = board.evaluate();
+        if stand_pat >= beta {
+            return Some(stand_pat);
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut captures: Vec<BoardMovement> = get_candidate_movements(board, &board.side_to_move)
+            .into_iter()
+            .filter(|movement| {
+                board.get_piece(&movement.destination).is_some()
+                    || movement.promotion == Some(PieceTypes::Queen)
+            })
+            .collect();
+        order_movements(board, &mut captures, None);
+
+        for movement in captures {
+            let Ok(applied_movement) = apply_movement(
+                board,
+                &movement.piece,
+                &movement.destination,
+                movement.promotion,
+            ) else {
+                continue;
+            };
+
+            let value = -self.quiescence(&applied_movement.board, -beta, -alpha)?;
+            if value >= beta {
+                return Some(value);
+            }
+            alpha = alpha.max(value);
+        }
+
+        Some(alpha)
+    }
+}
+
+/// Sorts the movements so the ones most likely to be good are searched first: the best
+/// movement of the previous depth, then captures of valuable pieces with cheap ones, then
+/// promotions.
+fn order_movements(
+    board: &Board,
+    movements: &mut [BoardMovement],
+    previous_best: Option<&BoardMovement>,
+) {
+    movements.sort_by_cached_key(|movement| {
+        if Some(movement) == previous_best {
+            return i32::MIN;
+        }
+
+        let capture = board.get_piece(&movement.destination).map_or(0, |victim| {
+            10 * board.piece_value(victim.kind()) - board.piece_value(movement.piece.kind()) / 10
+        });
+        let promotion = movement
+            .promotion
+            .map_or(0, |promotion| board.piece_value(&promotion));
+        -(capture + promotion)
+    });
+}
+
+/// Get's the movement of the board the transposition table remembers, if it can be made.
+fn find_table_movement(board: &Board, table_movement: TableMovement) -> Option<BoardMovement> {
+    get_candidate_movements(board, &board.side_to_move)
+        .into_iter()
+        .find(|movement| is_table_movement(movement, &table_movement))
+}
+
+fn is_table_movement(movement: &BoardMovement, (from, to, promotion): &TableMovement) -> bool {
+    movement.piece.board_position() == from
+        && &movement.destination == to
+        && &movement.promotion == promotion
+}
+
+/// The lowest value of a won position whose distance to the win is part of the value.
+const DISTANCE_SCORE_THRESHOLD: i32 = TABLEBASE_WIN_SCORE - 2 * MAX_SEARCH_DEPTH as i32;
+
+/// Get's the value to store on the transposition table. Mates and tablebase wins are stored as
+/// the distance from the position instead of from the root, so they can be used on any ply.
+fn value_to_table(value: i32, ply: usize) -> i32 {
+    match value {
+        value if value >= DISTANCE_SCORE_THRESHOLD => value + ply as i32,
+        value if value <= -DISTANCE_SCORE_THRESHOLD => value - ply as i32,
+        value => value,
+    }
+}
+
+fn value_from_table(value: i32, ply: usize) -> i32 {
+    match value {
+        value if value >= DISTANCE_SCORE_THRESHOLD => value - ply as i32,
+        value if value <= -DISTANCE_SCORE_THRESHOLD => value + ply as i32,
+        value => value,
+    }
+}
+
+/// Turns the value of a search into a score, telling apart the mates.
+fn score_from_value(value: i32) -> Evaluation {
+    let mate_distance = MATE_SCORE - value.abs();
+    if mate_distance <= MAX_SEARCH_DEPTH as i32 * 2 {
+        let moves = (mate_distance + 1) / 2;
+        Evaluation::Mate(if value > 0 { moves } else { -moves })
+    } else {
+        Evaluation::Centipawns(value)
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The skill level of the engine at full strength.
+pub const MAX_SKILL_LEVEL: u8 = 20;
+/// The rating of the weakest skill level.
+pub const MIN_SKILL_ELO: u32 = 800;
+/// The rating the strongest limited skill level plays at. It's a rough guess, not measured
+/// against rated players.
+pub const MAX_SKILL_ELO: u32 = 2200;
+/// The value of a line that mates, minus the moves it takes, when comparing lines.
+const MATE_LINE_VALUE: i32 = 100_000;
+
+/// How strong the engine plays. Below the full strength the search doesn't go as deep, the
+/// engine picks at random among the movements close to the best one, and from time to time it
+/// makes a mistake, playing a clearly worse but still plausible movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillLevel {
+    level: u8,
+}
+
+impl Default for SkillLevel {
+    fn default() -> Self {
+        SkillLevel::new(MAX_SKILL_LEVEL)
+    }
+}
+
+impl SkillLevel {
+    /// Creates a skill level from 0, the weakest, to `MAX_SKILL_LEVEL`, the full strength.
+    pub fn new(level: u8) -> Self {
+        SkillLevel {
+            level: level.min(MAX_SKILL_LEVEL),
+        }
+    }
+
+    /// Get's the skill level closest to a rating, between `MIN_SKILL_ELO` and `MAX_SKILL_ELO`.
+    /// Ratings above the highest one are still played below the full strength.
+    pub fn from_elo(elo: u32) -> Self {
+        let elo = elo.clamp(MIN_SKILL_ELO, MAX_SKILL_ELO);
+        let level =
+            (elo - MIN_SKILL_ELO) * (MAX_SKILL_LEVEL as u32 - 1) / (MAX_SKILL_ELO - MIN_SKILL_ELO);
+        SkillLevel::new(level as u8)
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Get's the rating the skill level roughly plays at, or `None` at the full strength.
+    pub fn elo(&self) -> Option<u32> {
+        (!self.is_full_strength()).then(|| {
+            MIN_SKILL_ELO
+                + self.level as u32 * (MAX_SKILL_ELO - MIN_SKILL_ELO) / (MAX_SKILL_LEVEL as u32 - 1)
+        })
+    }
+
+    pub fn is_full_strength(&self) -> bool {
+        self.level == MAX_SKILL_LEVEL
+    }
+
+    /// Get's the limits of a search at this skill level, going no deeper than the level allows.
+    pub fn limit_search(&self, mut limits: SearchLimits) -> SearchLimits {
+        if !self.is_full_strength() {
+            let max_depth = 1 + self.level as usize / 3;
+            limits.depth = Some(limits.depth.map_or(max_depth, |depth| depth.min(max_depth)));
+        }
+        limits
+    }
+
+    /// Get's how many lines the search must look for, so there are movements to choose from.
+    pub fn multi_pv(&self) -> usize {
+        if self.is_full_strength() {
+            1
+        } else {
+            4 + self.weakness() as usize / 4
+        }
+    }
+
+    /// Chooses the movement to play among the lines of a search.
+    /// At full strength it's always the best one. Otherwise it's, from time to time, a mistake
+    /// among the lines that don't lose much more than the best one, and the rest of the times a
+    /// movement whose score, with some noise added, is the highest. The weaker the level, the
+    /// more often the mistakes and the louder the noise. The board is the one that was searched.
+    pub fn choose_movement<R: Rng + ?Sized>(
+        &self,
+        board: &Board,
+        result: &SearchResult,
+        rng: &mut R,
+    ) -> Option<BoardMovement> {
+        let lines: Vec<(i32, &BoardMovement)> = result
+            .lines
+            .iter()
+            .filter_map(|line| Some((line_value(&line.score), line.movements.first()?)))
+            .collect();
+        let (best_value, best_movement) = *lines.first()?;
+        if self.is_full_strength() {
+            return Some(best_movement.clone());
+        }
+
+        let weakness = self.weakness() as i32;
+        let mistake_chance = weakness as f64 / 100.0;
+        if rng.gen_bool(mistake_chance) {
+            let max_loss = 100 + 20 * weakness;
+            let mistakes: Vec<&BoardMovement> = lines[1..]
+                .iter()
+                .filter(|(value, _)| {
+                    best_value - value <= max_loss
+                        && (*value > -MATE_LINE_VALUE / 2 || best_value < 0)
+                })
+                .map(|(_, movement)| *movement)
+                .collect();
+            if !mistakes.is_empty() {
+                return Some(mistakes[rng.gen_range(0..mistakes.len())].clone());
+            }
+        }
+
+        // The same noise Stockfish uses: worse movements are pushed up more, by up to a pawn.
+        let noise_weakness = 120 - 2 * self.level as i32;
+        let worst_value = lines.last().map_or(best_value, |(value, _)| *value);
+        let spread = (best_value - worst_value).min(board.piece_value(&PieceTypes::Pawn));
+        lines
+            .iter()
+            .max_by_key(|(value, _)| {
+                let noise = spread * rng.gen_range(0..noise_weakness);
+                let push = (noise_weakness * (best_value - value) + noise) / 128;
+                value + push
+            })
+            .map(|(_, movement)| (*movement).clone())
+    }
+
+    /// Get's how far the level is from the full strength.
+    fn weakness(&self) -> u8 {
+        MAX_SKILL_LEVEL - self.level
+    }
+}
+
+/// Searches the board and chooses the movement to play at the skill level.
+/// Returns `None` if the player can't move.
+pub fn search_skilled_move<R: Rng + ?Sized>(
+    board: &Board,
+    limits: SearchLimits,
+    skill_level: &SkillLevel,
+    rng: &mut R,
+) -> Option<BoardMovement> {
+    let result = Search::new(board.clone(), skill_level.limit_search(limits))
+        .with_multi_pv(skill_level.multi_pv())
+        .run()?;
+
+    skill_level.choose_movement(board, &result, rng)
+}
+
+/// Get's a value of the score that can be compared, with mates being worth more than any
+/// advantage.
+fn line_value(score: &Evaluation) -> i32 {
+    match *score {
+        Evaluation::Centipawns(centipawns) => centipawns,
+        Evaluation::Mate(moves) if moves > 0 => MATE_LINE_VALUE - moves,
+        Evaluation::Mate(moves) => -MATE_LINE_VALUE - moves,
+    }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+use shakmaty::{fen::Fen as ShakmatyFen, CastlingMode, Chess};
+use shakmaty_syzygy::Tablebase;
+use std::path::Path;
+
+/// The Syzygy endgame tablebases found on local directories, with the `.rtbw` files for the
+/// results and the `.rtbz` files for the distances to zero.
+#[derive(Debug, Default)]
+pub struct SyzygyTablebase {
+    tables: Tablebase<Chess>,
+}
+
+impl SyzygyTablebase {
+    pub fn new() -> Self {
+        SyzygyTablebase::default()
+    }
+
+    /// Creates a tablebase with the tables of a directory.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut tablebase = SyzygyTablebase::new();
+        tablebase.add_directory(path)?;
+        Ok(tablebase)
+    }
+
+    /// Adds the tables of a directory, returning how many files were added.
+    /// The files are only read when a position is probed.
+    pub fn add_directory(&mut self, path: impl AsRef<Path>) -> std::io::Result<usize> {
+        self.tables.add_directory(path)
+    }
+}
+
+impl EndgameTablebase for SyzygyTablebase {
+    fn max_pieces(&self) -> usize {
+        self.tables.max_pieces()
+    }
+
+    fn probe_wdl(&self, board: &Board) -> Option<Wdl> {
+        let position = to_position(board)?;
+        let wdl = self.tables.probe_wdl_after_zeroing(&position).ok()?;
+
+        Some(match wdl {
+            shakmaty_syzygy::Wdl::Loss => Wdl::Loss,
+            shakmaty_syzygy::Wdl::BlessedLoss => Wdl::BlessedLoss,
+            shakmaty_syzygy::Wdl::Draw => Wdl::Draw,
+            shakmaty_syzygy::Wdl::CursedWin => Wdl::CursedWin,
+            shakmaty_syzygy::Wdl::Win => Wdl::Win,
+        })
+    }
+
+    fn probe_dtz(&self, board: &Board) -> Option<i32> {
+        let position = to_position(board)?;
+        let dtz = self.tables.probe_dtz(&position).ok()?;
+
+        Some(dtz.ignore_rounding().0)
+    }
+}
+
+/// Get's the position the tablebases can probe, or `None` if the board is not a standard one.
+fn to_position(board: &Board) -> Option<Chess> {
+    if board.dimensions() != BoardDimensions::STANDARD {
+        return None;
+    }
+
+    ShakmatyFen::from_ascii(board.to_fen().as_bytes())
+        .ok()?
+        .into_position(CastlingMode::Standard)
+        .ok()
+}
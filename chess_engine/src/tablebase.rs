@@ -0,0 +1,240 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// The highest distance to a capture or pawn movement that still wins before the fifty move rule
+/// can be claimed.
+const FIFTY_MOVE_RULE_DTZ: i32 = 100;
+
+/// The result of a position with perfect play, for the player that moves next, taking the fifty
+/// move rule into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Wdl {
+    Loss,
+    /// A loss that the fifty move rule turns into a draw.
+    BlessedLoss,
+    Draw,
+    /// A win that the fifty move rule turns into a draw.
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    /// Get's the result for the other player.
+    pub fn flip(self) -> Self {
+        match self {
+            Wdl::Loss => Wdl::Win,
+            Wdl::BlessedLoss => Wdl::CursedWin,
+            Wdl::Draw => Wdl::Draw,
+            Wdl::CursedWin => Wdl::BlessedLoss,
+            Wdl::Win => Wdl::Loss,
+        }
+    }
+
+    /// Get's the result of a position from its distance to zero.
+    pub fn from_dtz(dtz: i32) -> Self {
+        match dtz {
+            dtz if dtz < -FIFTY_MOVE_RULE_DTZ => Wdl::BlessedLoss,
+            dtz if dtz < 0 => Wdl::Loss,
+            0 => Wdl::Draw,
+            dtz if dtz <= FIFTY_MOVE_RULE_DTZ => Wdl::Win,
+            _ => Wdl::CursedWin,
+        }
+    }
+}
+
+/// How far a position is from mate with perfect play, in plies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistanceToMate {
+    /// The player that moves next mates in the given plies.
+    Win(usize),
+    /// The player that moves next is mated in the given plies.
+    Loss(usize),
+    Draw,
+}
+
+/// What a tablebase knows about a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TablebaseProbe {
+    pub wdl: Wdl,
+    /// The distance to zero: how many plies it takes, with perfect play, until the next capture
+    /// or pawn movement. Positive when the player that moves next wins, negative when it loses
+    /// and zero on draws. `None` if the tablebase only knows the result.
+    pub dtz: Option<i32>,
+    /// The distance to mate, or `None` if the tablebase doesn't know it.
+    pub dtm: Option<DistanceToMate>,
+}
+
+/// A collection of endgames solved for every position, that the search can use instead of
+/// looking ahead.
+pub trait EndgameTablebase: std::fmt::Debug + Send + Sync {
+    /// The most pieces, kings included, a position can have to be on the tablebase.
+    fn max_pieces(&self) -> usize;
+
+    /// Get's the result of the position, supposing it was reached with a capture or a pawn
+    /// movement. Returns `None` if the position is not on the tablebase.
+    fn probe_wdl(&self, board: &Board) -> Option<Wdl>;
+
+    /// Get's the distance to zero of the position, or `None` if it's not on the tablebase.
+    fn probe_dtz(&self, board: &Board) -> Option<i32>;
+
+    /// Get's the distance to mate of the position, or `None` if it's not on the tablebase or the
+    /// tablebase doesn't know it.
+    fn probe_dtm(&self, _board: &Board) -> Option<DistanceToMate> {
+        None
+    }
+
+    /// Get's everything the tablebase knows about the position, or `None` if it's not on the
+    /// tablebase.
+    fn probe(&self, board: &Board) -> Option<TablebaseProbe> {
+        if !is_in_tablebase(self, board) {
+            return None;
+        }
+
+        let wdl = self.probe_wdl(board)?;
+        Some(TablebaseProbe {
+            wdl,
+            dtz: self.probe_dtz(board),
+            dtm: self.probe_dtm(board),
+        })
+    }
+}
+
+/// Checks if the position can be on the tablebase: it must be a standard board with few enough
+/// pieces and no castling rights.
+pub(crate) fn is_in_tablebase<T: EndgameTablebase + ?Sized>(tablebase: &T, board: &Board) -> bool {
+    board.dimensions() == BoardDimensions::STANDARD
+        && board.white_pieces.len() + board.black_pieces.len() <= tablebase.max_pieces()
+        && board.white_castling.is_none()
+        && board.black_castling.is_none()
+}
+
+/// Get's the legal movements of the board that keep the best result the tablebase knows, or
+/// `None` if the position is not on the tablebase.
+/// When the position is won or lost only the movements that win the fastest, or lose the
+/// slowest, are kept, so the search doesn't wander around a won position. The movements are
+/// ranked by their distance to mate when the tablebase knows it for all of them, and by their
+/// distance to zero otherwise.
+pub(crate) fn get_tablebase_movements<T: EndgameTablebase + ?Sized>(
+    tablebase: &T,
+    board: &Board,
+) -> Option<Vec<BoardMovement>> {
+    if !is_in_tablebase(tablebase, board) {
+        return None;
+    }
+
+    let mut movements = vec![];
+    for movement in get_legal_movements(board, &board.side_to_move) {
+        let applied_movement = apply_movement(
+            board,
+            &movement.piece,
+            &movement.destination,
+            movement.promotion,
+        )
+        .ok()?;
+        movements.push((movement, applied_movement));
+    }
+
+    let ranks: Vec<(Wdl, i32)> = movements
+        .iter()
+        .map(|(_, applied_movement)| {
+            let dtm = get_movement_dtm(tablebase, applied_movement)?;
+            let wdl = match dtm.signum() {
+                1 => Wdl::Win,
+                -1 => Wdl::Loss,
+                _ => Wdl::Draw,
+            };
+            Some((wdl, -dtm))
+        })
+        .collect::<Option<_>>()
+        .or_else(|| {
+            movements
+                .iter()
+                .map(|(movement, applied_movement)| {
+                    let dtz = get_movement_dtz(tablebase, movement, applied_movement)?;
+                    Some((Wdl::from_dtz(dtz), -dtz))
+                })
+                .collect()
+        })?;
+    let ranked_movements: Vec<((Wdl, i32), BoardMovement)> = ranks
+        .into_iter()
+        .zip(movements.into_iter().map(|(movement, _)| movement))
+        .collect();
+
+    let best_rank = ranked_movements.iter().map(|(rank, _)| *rank).max()?;
+    let movements = ranked_movements
+        .into_iter()
+        .filter(|((wdl, dtz), _)| match best_rank {
+            (Wdl::Draw, _) => *wdl == Wdl::Draw,
+            _ => (*wdl, *dtz) == best_rank,
+        })
+        .map(|(_, movement)| movement)
+        .collect();
+
+    Some(movements)
+}
+
+/// Get's the distance to mate, in plies, of the position before the movement, if the movement is
+/// played. It's positive when the movement wins and negative when it loses.
+fn get_movement_dtm<T: EndgameTablebase + ?Sized>(
+    tablebase: &T,
+    applied_movement: &AppliedMovement,
+) -> Option<i32> {
+    let board = &applied_movement.board;
+    if get_legal_movements(board, &board.side_to_move).is_empty() {
+        return Some(if board.is_in_check(&board.side_to_move) {
+            1
+        } else {
+            0
+        });
+    }
+    // The tablebase may not have the endgames that can't be won, like a lone king.
+    if board.is_insufficient_material() {
+        return Some(0);
+    }
+
+    Some(match tablebase.probe_dtm(board)? {
+        DistanceToMate::Win(plies) => -(plies as i32) - 1,
+        DistanceToMate::Loss(plies) => plies as i32 + 1,
+        DistanceToMate::Draw => 0,
+    })
+}
+
+/// Get's the distance to zero of the position before the movement, if the movement is played.
+fn get_movement_dtz<T: EndgameTablebase + ?Sized>(
+    tablebase: &T,
+    movement: &BoardMovement,
+    applied_movement: &AppliedMovement,
+) -> Option<i32> {
+    let board = &applied_movement.board;
+    if get_legal_movements(board, &board.side_to_move).is_empty() {
+        return Some(if board.is_in_check(&board.side_to_move) {
+            1
+        } else {
+            0
+        });
+    }
+    // The tablebase may not have the endgames that can't be won, like a lone king.
+    if board.is_insufficient_material() {
+        return Some(0);
+    }
+
+    let is_zeroing =
+        movement.piece.kind() == &PieceTypes::Pawn || applied_movement.captured_piece.is_some();
+    if is_zeroing {
+        return Some(match tablebase.probe_wdl(board)?.flip() {
+            Wdl::Loss => -1,
+            Wdl::BlessedLoss => -FIFTY_MOVE_RULE_DTZ - 1,
+            Wdl::Draw => 0,
+            Wdl::CursedWin => FIFTY_MOVE_RULE_DTZ + 1,
+            Wdl::Win => 1,
+        });
+    }
+
+    let dtz = tablebase.probe_dtz(board)?;
+    Some(match dtz.signum() {
+        1 => -dtz - 1,
+        -1 => -dtz + 1,
+        _ => 0,
+    })
+}
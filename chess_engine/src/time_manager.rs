@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The time kept aside on each movement for the delay between the search ending and the
+/// movement reaching the clock.
+pub const DEFAULT_MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+/// How many movements the remaining time is split between when the clock doesn't say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+/// How many times the usual time of a movement the search can take on unstable positions.
+const MAX_TIME_EXTENSION: u32 = 4;
+/// The score drop, in centipawns, that makes the search take the most extra time.
+const MAX_SCORE_DROP: i32 = 100;
+
+/// The clock of the player that's about to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameClock {
+    pub remaining: Duration,
+    /// The time added to the clock after each movement.
+    pub increment: Duration,
+    /// How many movements must be made before more time is added, or `None` if the remaining
+    /// time is for the rest of the game.
+    pub moves_to_go: Option<u32>,
+}
+
+impl GameClock {
+    /// Creates a clock from milliseconds, the way game servers and UCI GUIs keep them.
+    pub fn from_millis(remaining_ms: u128, increment_ms: u128, moves_to_go: Option<u32>) -> Self {
+        let millis = |ms: u128| Duration::from_millis(ms.min(u64::MAX as u128) as u64);
+        GameClock {
+            remaining: millis(remaining_ms),
+            increment: millis(increment_ms),
+            moves_to_go,
+        }
+    }
+}
+
+/// Decides how long a search can take with the time left on the clock.
+/// The search shouldn't start another depth after the soft limit, and it's stopped right away
+/// at the hard limit. The soft limit is extended, up to the hard one, while the best movement
+/// keeps changing or the score drops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeManager {
+    soft_limit: Duration,
+    hard_limit: Duration,
+}
+
+impl TimeManager {
+    pub fn new(clock: &GameClock) -> Self {
+        TimeManager::with_move_overhead(clock, DEFAULT_MOVE_OVERHEAD)
+    }
+
+    /// Creates a time manager that keeps `move_overhead` aside on each movement, so slow
+    /// connections don't make the player lose on time.
+    pub fn with_move_overhead(clock: &GameClock, move_overhead: Duration) -> Self {
+        let available = clock.remaining.saturating_sub(move_overhead);
+        let moves_to_go = clock.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+
+        let hard_limit = if moves_to_go == 1 {
+            available * 9 / 10
+        } else {
+            available * 3 / 4
+        };
+        let soft_limit = (available / moves_to_go + clock.increment * 3 / 4).min(hard_limit);
+        let hard_limit = hard_limit.min(soft_limit * MAX_TIME_EXTENSION);
+
+        TimeManager {
+            soft_limit,
+            hard_limit,
+        }
+    }
+
+    /// Get's the time after which the search shouldn't start another depth, on a stable
+    /// position.
+    pub fn soft_limit(&self) -> Duration {
+        self.soft_limit
+    }
+
+    /// Get's the time at which the search is stopped even in the middle of a depth.
+    pub fn hard_limit(&self) -> Duration {
+        self.hard_limit
+    }
+
+    /// Get's the soft limit extended by how unstable the position is: `best_move_changes` is how
+    /// often the best movement changed on the last depths, and `score_drop` how many centipawns
+    /// the score fell on the last one.
+    pub(crate) fn extended_soft_limit(&self, best_move_changes: f64, score_drop: i32) -> Duration {
+        let drop_factor = score_drop.clamp(0, MAX_SCORE_DROP) as f64 / MAX_SCORE_DROP as f64;
+        let factor = (1.0 + best_move_changes) * (1.0 + drop_factor);
+
+        self.soft_limit.mul_f64(factor).min(self.hard_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_starting_board, Search, SearchLimits};
+
+    #[test]
+    fn uses_most_of_the_time_on_the_last_movement_before_more_is_added() {
+        let clock = GameClock::from_millis(10_030, 1_000, Some(1));
+        let time_manager = TimeManager::with_move_overhead(&clock, Duration::from_millis(30));
+
+        assert_eq!(time_manager.hard_limit(), Duration::from_millis(9_000));
+        assert_eq!(time_manager.soft_limit(), time_manager.hard_limit());
+
+        let clock = GameClock::from_millis(10_030, 1_000, Some(2));
+        let time_manager = TimeManager::with_move_overhead(&clock, Duration::from_millis(30));
+
+        assert_eq!(time_manager.soft_limit(), Duration::from_millis(5_750));
+        assert_eq!(time_manager.hard_limit(), Duration::from_millis(7_500));
+    }
+
+    #[test]
+    fn completes_the_first_depth_without_time_left() {
+        let clock = GameClock::from_millis(10, 0, None);
+        let time_manager = TimeManager::with_move_overhead(&clock, Duration::from_millis(30));
+
+        assert_eq!(time_manager.soft_limit(), Duration::ZERO);
+        assert_eq!(time_manager.hard_limit(), Duration::ZERO);
+
+        let result = Search::new(get_starting_board(), SearchLimits::default())
+            .with_time_manager(time_manager)
+            .run()
+            .unwrap();
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1);
+    }
+
+    #[test]
+    fn extends_the_soft_limit_up_to_the_hard_one() {
+        let clock = GameClock::from_millis(60_030, 0, None);
+        let time_manager = TimeManager::with_move_overhead(&clock, Duration::from_millis(30));
+
+        assert_eq!(time_manager.soft_limit(), Duration::from_millis(2_000));
+        assert_eq!(time_manager.hard_limit(), Duration::from_millis(8_000));
+
+        assert_eq!(
+            time_manager.extended_soft_limit(0.0, 0),
+            time_manager.soft_limit()
+        );
+        assert_eq!(
+            time_manager.extended_soft_limit(0.0, -300),
+            time_manager.soft_limit()
+        );
+        assert_eq!(
+            time_manager.extended_soft_limit(1.0, 50),
+            Duration::from_millis(6_000)
+        );
+        assert_eq!(
+            time_manager.extended_soft_limit(10.0, 1_000),
+            time_manager.hard_limit()
+        );
+    }
+}
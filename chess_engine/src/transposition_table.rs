@@ -0,0 +1,234 @@
+use super::*;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The size, in megabytes, of the transposition table of a search when no other is given.
+pub const DEFAULT_HASH_SIZE_MB: usize = 16;
+/// The size in bytes of each entry of the table: the key and the data.
+const ENTRY_SIZE: usize = 16;
+/// How the value of an entry relates to the real value of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bound {
+    Exact,
+    /// The real value is at least this one: a movement was good enough to cut the search.
+    Lower,
+    /// The real value is at most this one: no movement beat the alpha of the search.
+    Upper,
+}
+
+/// The origin, destination and promotion of a movement stored on the table.
+pub(crate) type TableMovement = (BoardPosition, BoardPosition, Option<PieceTypes>);
+
+/// What a search found about a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TableEntry {
+    pub value: i32,
+    pub depth: usize,
+    pub bound: Bound,
+    /// The best movement found, if any.
+    pub movement: Option<TableMovement>,
+}
+
+/// A table of positions already searched, shared between the threads of a search and between
+/// the searches of a game, so positions reached more than once are only searched once.
+/// Entries are written without locks: the key is stored mixed with the data, so an entry half
+/// written by another thread doesn't match any position.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    entries: Vec<[AtomicU64; 2]>,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new(DEFAULT_HASH_SIZE_MB)
+    }
+}
+
+impl TranspositionTable {
+    /// Creates a table that takes about `size_mb` megabytes of memory.
+    pub fn new(size_mb: usize) -> Self {
+        let length = (size_mb * 1024 * 1024 / ENTRY_SIZE).max(1);
+        TranspositionTable {
+            entries: (0..length)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+        }
+    }
+
+    /// Get's how many positions the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Forgets every position, like when a new game starts.
+    pub fn clear(&self) {
+        for [key, data] in &self.entries {
+            key.store(0, Ordering::Relaxed);
+            data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Get's how full the table is, in thousandths, looking at its first entries.
+    pub fn hashfull(&self) -> usize {
+        let sample = &self.entries[..self.entries.len().min(1000)];
+        let used = sample
+            .iter()
+            .filter(|[_, data]| data.load(Ordering::Relaxed) != 0)
+            .count();
+        used * 1000 / sample.len()
+    }
+
+    pub(crate) fn probe(&self, hash: u64) -> Option<TableEntry> {
+        let [key, data] = &self.entries[self.index(hash)];
+        let data = data.load(Ordering::Relaxed);
+        if data == 0 || key.load(Ordering::Relaxed) ^ data != hash {
+            return None;
+        }
+
+        decode_entry(data)
+    }
+
+    /// Stores what a search found about a position, unless the table knows about a deeper
+    /// search of the same position.
+    pub(crate) fn store(&self, hash: u64, entry: TableEntry) {
+        let [key, data] = &self.entries[self.index(hash)];
+        let is_deeper_known = self.probe(hash).map_or(false, |known| {
+            known.depth > entry.depth && entry.bound != Bound::Exact
+        });
+        if is_deeper_known {
+            return;
+        }
+
+        let new_data = encode_entry(&entry);
+        key.store(hash ^ new_data, Ordering::Relaxed);
+        data.store(new_data, Ordering::Relaxed);
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        (hash % self.entries.len() as u64) as usize
+    }
+}
+
+/// Packs an entry on 64 bits: the value on 24 bits, which hold even the mate scores, the symbol of
+/// the piece the movement promotes to, the depth, the bound, a bit that's always set so no entry
+/// is packed as zero, a bit telling if there's a movement, and the origin and destination of the
+/// movement.
+fn encode_entry(entry: &TableEntry) -> u64 {
+    let bound = match entry.bound {
+        Bound::Exact => 0,
+        Bound::Lower => 1,
+        Bound::Upper => 2,
+    };
+    let square = |position: &BoardPosition| {
+        let (row, column) = position.into();
+        (row * MAX_FILES + column) as u64
+    };
+    let movement = match &entry.movement {
+        Some((from, to, promotion)) => {
+            let promotion = promotion.map_or(0, |kind| kind.symbol() as u64 & 0x7f);
+            promotion << 32 | 1 << 20 | square(from) << 8 | square(to)
+        }
+        None => 0,
+    };
+
+    ((entry.value as u64) & 0xff_ffff) << 40
+        | (entry.depth.min(u8::MAX as usize) as u64) << 24
+        | bound << 22
+        | 1 << 21
+        | movement
+}
+
+fn decode_entry(data: u64) -> Option<TableEntry> {
+    let bound = match (data >> 22) & 3 {
+        0 => Bound::Exact,
+        1 => Bound::Lower,
+        _ => Bound::Upper,
+    };
+    let square = |index: u64| {
+        let index = index as usize & 0xff;
+        BoardPosition::try_from((index / MAX_FILES, index % MAX_FILES)).ok()
+    };
+    let movement = if data & 1 << 20 != 0 {
+        let promotion = match (data >> 32) & 0x7f {
+            0 => None,
+            symbol => {
+                let symbol = symbol as u8 as char;
+                Some(PieceTypes::from_symbol(symbol).unwrap_or(PieceTypes::Fairy(symbol)))
+            }
+        };
+        Some((square(data >> 8)?, square(data)?, promotion))
+    } else {
+        None
+    };
+
+    Some(TableEntry {
+        value: (data as i64 >> 40) as i32,
+        depth: ((data >> 24) & 0xff) as usize,
+        bound,
+        movement,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(value: i32, depth: usize, bound: Bound) -> TableEntry {
+        let position = |row: usize, column: usize| BoardPosition::try_from((row, column)).unwrap();
+        TableEntry {
+            value,
+            depth,
+            bound,
+            movement: Some((position(6, 4), position(7, 4), Some(PieceTypes::CHANCELLOR))),
+        }
+    }
+
+    #[test]
+    fn stored_entries_are_probed_back() {
+        let table = TranspositionTable::new(1);
+        let hash = 0x463b96181691fc9c;
+        let stored = entry(-999_997, 7, Bound::Upper);
+
+        assert_eq!(table.probe(hash), None);
+        table.store(hash, stored.clone());
+        assert_eq!(table.probe(hash), Some(stored));
+
+        let without_movement = TableEntry {
+            movement: None,
+            ..entry(25, 1, Bound::Exact)
+        };
+        table.store(hash, without_movement.clone());
+        assert_eq!(table.probe(hash), Some(without_movement));
+    }
+
+    #[test]
+    fn deeper_entries_are_kept_unless_the_new_one_is_exact() {
+        let table = TranspositionTable::new(1);
+        let hash = 0x823c9b50fd114196;
+        let deep = entry(40, 8, Bound::Lower);
+
+        table.store(hash, deep.clone());
+        table.store(hash, entry(-10, 3, Bound::Upper));
+        assert_eq!(table.probe(hash), Some(deep));
+
+        let exact = entry(-10, 3, Bound::Exact);
+        table.store(hash, exact.clone());
+        assert_eq!(table.probe(hash), Some(exact));
+    }
+
+    #[test]
+    fn colliding_positions_replace_each_other() {
+        let table = TranspositionTable::new(1);
+        let hash = 0x0756b94461c50fb0;
+        let colliding_hash = hash + table.capacity() as u64;
+        assert_eq!(table.index(hash), table.index(colliding_hash));
+
+        table.store(hash, entry(100, 9, Bound::Exact));
+        assert_eq!(table.probe(colliding_hash), None);
+
+        let colliding = entry(-50, 2, Bound::Lower);
+        table.store(colliding_hash, colliding.clone());
+        assert_eq!(table.probe(colliding_hash), Some(colliding));
+        assert_eq!(table.probe(hash), None);
+    }
+}
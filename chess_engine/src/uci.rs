@@ -0,0 +1,436 @@
+use super::*;
+
+use rand::{rngs::SmallRng, SeedableRng};
+use std::{
+    io::{BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime},
+};
+
+const ENGINE_NAME: &str = "Chessy";
+const ENGINE_AUTHOR: &str = "the Chessy developers";
+/// The biggest transposition table a GUI can ask for, in megabytes.
+const MAX_HASH_SIZE_MB: usize = 65_536;
+
+/// The options of the engine a GUI can change with `setoption`.
+#[derive(Debug, Clone)]
+pub struct UciOptions {
+    /// How many lines, each starting with a different movement, are searched and reported.
+    pub multi_pv: usize,
+    /// The time kept aside on each movement for the delay of the GUI.
+    pub move_overhead: Duration,
+    pub threads: usize,
+    /// The size of the transposition table, in megabytes.
+    pub hash_size_mb: usize,
+    pub skill_level: SkillLevel,
+    /// Whether to play at the rating of `elo` instead of at the skill level.
+    pub limit_strength: bool,
+    pub elo: u32,
+}
+
+impl UciOptions {
+    /// Get's the skill level the engine plays at.
+    pub fn playing_skill_level(&self) -> SkillLevel {
+        if self.limit_strength {
+            SkillLevel::from_elo(self.elo)
+        } else {
+            self.skill_level
+        }
+    }
+}
+
+impl Default for UciOptions {
+    fn default() -> Self {
+        UciOptions {
+            multi_pv: 1,
+            move_overhead: DEFAULT_MOVE_OVERHEAD,
+            threads: 1,
+            hash_size_mb: DEFAULT_HASH_SIZE_MB,
+            skill_level: SkillLevel::default(),
+            limit_strength: false,
+            elo: MAX_SKILL_ELO,
+        }
+    }
+}
+
+/// A chess engine that talks the Universal Chess Interface protocol, so it can be used from
+/// chess GUIs. Searches run on their own thread, so `stop` can be answered while searching.
+pub struct UciEngine<W: Write + Send + 'static> {
+    output: Arc<Mutex<W>>,
+    options: UciOptions,
+    game: ChessGame,
+    /// Kept between the searches of a game, and cleared when a new one starts.
+    transposition_table: Arc<TranspositionTable>,
+    stop_signal: Arc<AtomicBool>,
+    search_thread: Option<JoinHandle<()>>,
+}
+
+impl<W: Write + Send + 'static> UciEngine<W> {
+    pub fn new(output: W) -> Self {
+        UciEngine {
+            output: Arc::new(Mutex::new(output)),
+            options: UciOptions::default(),
+            game: ChessGame::new(get_starting_board(), GameVariant::Standard),
+            transposition_table: Arc::new(TranspositionTable::default()),
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            search_thread: None,
+        }
+    }
+
+    pub fn options(&self) -> &UciOptions {
+        &self.options
+    }
+
+    /// Reads commands until `quit` is received or the input ends.
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if !self.handle_command(&line) {
+                return;
+            }
+        }
+        self.stop_search();
+    }
+
+    /// Handles a command of the GUI. Unknown commands are ignored, as the protocol asks.
+    /// Returns `false` when the engine should quit.
+    pub fn handle_command(&mut self, line: &str) -> bool {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uci") => {
+                write_lines(
+                    &self.output,
+                    &[
+                        format!("id name {ENGINE_NAME}"),
+                        format!("id author {ENGINE_AUTHOR}"),
+                        format!(
+                            "option name MultiPV type spin default 1 min 1 max {}",
+                            MAX_MULTI_PV
+                        ),
+                        format!(
+                            "option name Threads type spin default 1 min 1 max {}",
+                            MAX_SEARCH_THREADS
+                        ),
+                        format!(
+                            "option name Hash type spin default {} min 1 max {}",
+                            DEFAULT_HASH_SIZE_MB, MAX_HASH_SIZE_MB
+                        ),
+                        format!(
+                            "option name Skill Level type spin default {0} min 0 max {0}",
+                            MAX_SKILL_LEVEL
+                        ),
+                        "option name UCI_LimitStrength type check default false".to_string(),
+                        format!(
+                            "option name UCI_Elo type spin default {1} min {0} max {1}",
+                            MIN_SKILL_ELO, MAX_SKILL_ELO
+                        ),
+                        format!(
+                            "option name Move Overhead type spin default {} min 0 max 5000",
+                            DEFAULT_MOVE_OVERHEAD.as_millis()
+                        ),
+                        "uciok".to_string(),
+                    ],
+                );
+            }
+            Some("isready") => write_lines(&self.output, &["readyok".to_string()]),
+            Some("setoption") => self.set_option(tokens.collect()),
+            Some("ucinewgame") => {
+                self.stop_search();
+                self.game = ChessGame::new(get_starting_board(), GameVariant::Standard);
+                self.transposition_table.clear();
+            }
+            Some("position") => {
+                self.stop_search();
+                self.set_position(tokens.collect());
+            }
+            Some("go") => {
+                self.stop_search();
+                self.start_search(tokens.collect());
+            }
+            Some("stop") => self.stop_search(),
+            Some("quit") => {
+                self.stop_search();
+                return false;
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    /// Handles `setoption name <name> value <value>`.
+    fn set_option(&mut self, tokens: Vec<&str>) {
+        let value_index = tokens.iter().position(|token| *token == "value");
+        let name = tokens[..value_index.unwrap_or(tokens.len())]
+            .iter()
+            .skip_while(|token| **token == "name")
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = value_index.map(|index| tokens[index + 1..].join(" "));
+
+        if name.eq_ignore_ascii_case("MultiPV") {
+            match value.and_then(|value| value.parse::<usize>().ok()) {
+                Some(lines) => self.options.multi_pv = lines.clamp(1, MAX_MULTI_PV),
+                None => self.write_info_string("MultiPV needs a number as its value"),
+            }
+        } else if name.eq_ignore_ascii_case("Threads") {
+            match value.and_then(|value| value.parse::<usize>().ok()) {
+                Some(threads) => self.options.threads = threads.clamp(1, MAX_SEARCH_THREADS),
+                None => self.write_info_string("Threads needs a number as its value"),
+            }
+        } else if name.eq_ignore_ascii_case("Hash") {
+            match value.and_then(|value| value.parse::<usize>().ok()) {
+                Some(size_mb) => {
+                    self.stop_search();
+                    self.options.hash_size_mb = size_mb.clamp(1, MAX_HASH_SIZE_MB);
+                    self.transposition_table =
+                        Arc::new(TranspositionTable::new(self.options.hash_size_mb));
+                }
+                None => self.write_info_string("Hash needs a number as its value"),
+            }
+        } else if name.eq_ignore_ascii_case("Skill Level") {
+            match value.and_then(|value| value.parse::<u8>().ok()) {
+                Some(level) => self.options.skill_level = SkillLevel::new(level),
+                None => self.write_info_string("Skill Level needs a number as its value"),
+            }
+        } else if name.eq_ignore_ascii_case("UCI_LimitStrength") {
+            match value.and_then(|value| value.parse::<bool>().ok()) {
+                Some(limit_strength) => self.options.limit_strength = limit_strength,
+                None => {
+                    self.write_info_string("UCI_LimitStrength needs true or false as its value")
+                }
+            }
+        } else if name.eq_ignore_ascii_case("UCI_Elo") {
+            match value.and_then(|value| value.parse::<u32>().ok()) {
+                Some(elo) => self.options.elo = elo.clamp(MIN_SKILL_ELO, MAX_SKILL_ELO),
+                None => self.write_info_string("UCI_Elo needs a number as its value"),
+            }
+        } else if name.eq_ignore_ascii_case("Move Overhead") {
+            match value.and_then(|value| value.parse::<u64>().ok()) {
+                Some(ms) => self.options.move_overhead = Duration::from_millis(ms.min(5000)),
+                None => self.write_info_string("Move Overhead needs a number as its value"),
+            }
+        }
+    }
+
+    /// Handles `position startpos` and `position fen <fen>`, followed by `moves` and the
+    /// movements played since.
+    fn set_position(&mut self, tokens: Vec<&str>) {
+        let moves_index = tokens.iter().position(|token| *token == "moves");
+        let (position, moves) = tokens.split_at(moves_index.unwrap_or(tokens.len()));
+
+        let game = match position {
+            ["startpos"] => Ok(ChessGame::new(get_starting_board(), GameVariant::Standard)),
+            ["fen", fen @ ..] => ChessGame::from_fen(&fen.join(" "), GameVariant::Standard),
+            _ => {
+                self.write_info_string("the position must be `startpos` or `fen <fen>`");
+                return;
+            }
+        };
+        let mut game = match game {
+            Ok(game) => game,
+            Err(error) => {
+                self.write_info_string(&error.to_string());
+                return;
+            }
+        };
+
+        for movement in moves.iter().skip(1) {
+            if let Err(error) = game.make_uci_move(movement) {
+                self.write_info_string(&format!("`{movement}` can't be played: {error}"));
+                break;
+            }
+        }
+        self.game = game;
+    }
+
+    /// Handles `go`, starting a search on another thread that prints its progress and the best
+    /// movement once it's done. With the time left on the clocks, the time manager decides how
+    /// long to search. Without limits, or with `infinite`, it searches until `stop`.
+    fn start_search(&mut self, tokens: Vec<&str>) {
+        let mut limits = SearchLimits {
+            depth: None,
+            nodes: None,
+            movetime: None,
+        };
+        let mut is_infinite = false;
+        let (mut white_ms, mut black_ms) = (None, None);
+        let (mut white_increment_ms, mut black_increment_ms) = (0, 0);
+        let mut moves_to_go = None;
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            // GUIs can send negative times when the clock ran out.
+            let mut number = || {
+                tokens
+                    .next()
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .map(|value| value.max(0) as u64)
+            };
+            match token {
+                "depth" => limits.depth = number().map(|depth| depth as usize),
+                "nodes" => limits.nodes = number(),
+                "movetime" => limits.movetime = number().map(Duration::from_millis),
+                "wtime" => white_ms = number(),
+                "btime" => black_ms = number(),
+                "winc" => white_increment_ms = number().unwrap_or(0),
+                "binc" => black_increment_ms = number().unwrap_or(0),
+                "movestogo" => moves_to_go = number().map(|moves| moves as u32),
+                "infinite" => is_infinite = true,
+                _ => {}
+            }
+        }
+
+        let board = self.game.board().clone();
+        let clock = match board.side_to_move {
+            PieceColors::White => white_ms.map(|ms| (ms, white_increment_ms)),
+            PieceColors::Black => black_ms.map(|ms| (ms, black_increment_ms)),
+        }
+        .filter(|_| !is_infinite)
+        .map(|(ms, increment_ms)| {
+            GameClock::from_millis(ms as u128, increment_ms as u128, moves_to_go)
+        });
+
+        let skill_level = self.options.playing_skill_level();
+        self.stop_signal = Arc::new(AtomicBool::new(false));
+        let mut search = Search::new(board.clone(), skill_level.limit_search(limits))
+            .with_history(get_history(&self.game))
+            .with_multi_pv(self.options.multi_pv.max(skill_level.multi_pv()))
+            .with_threads(self.options.threads)
+            .with_transposition_table(self.transposition_table.clone())
+            .with_stop_signal(self.stop_signal.clone());
+        if let Some(clock) = clock {
+            search = search.with_time_manager(TimeManager::with_move_overhead(
+                &clock,
+                self.options.move_overhead,
+            ));
+        }
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        let task = SearchTask {
+            board,
+            search,
+            transposition_table: self.transposition_table.clone(),
+            stop_signal: self.stop_signal.clone(),
+            is_infinite,
+            reported_lines: self.options.multi_pv,
+            skill_level,
+            rng: SmallRng::seed_from_u64(seed),
+        };
+        let output = self.output.clone();
+        self.search_thread = Some(std::thread::spawn(move || task.run(&output)));
+    }
+
+    /// Stops the search if there's one running, waiting until it prints its best movement.
+    fn stop_search(&mut self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        if let Some(search_thread) = self.search_thread.take() {
+            let _ = search_thread.join();
+        }
+    }
+
+    fn write_info_string(&self, message: &str) {
+        write_lines(&self.output, &[format!("info string {message}")]);
+    }
+}
+
+/// A search started by `go`, with everything it needs to run on its own thread.
+struct SearchTask {
+    /// The position being searched.
+    board: Board,
+    search: Search,
+    transposition_table: Arc<TranspositionTable>,
+    stop_signal: Arc<AtomicBool>,
+    is_infinite: bool,
+    /// How many lines are printed, which can be fewer than the ones searched when the skill
+    /// level needs more to choose from.
+    reported_lines: usize,
+    skill_level: SkillLevel,
+    rng: SmallRng,
+}
+
+impl SearchTask {
+    /// Searches until a limit is reached or the search is stopped, printing the lines of each
+    /// completed depth and then the movement chosen at the skill level. An infinite search
+    /// waits to be stopped before printing the movement, even if it finished earlier.
+    fn run<W: Write>(mut self, output: &Mutex<W>) {
+        let start = Instant::now();
+        while let Some(result) = self.search.search_next_depth() {
+            let elapsed = start.elapsed().as_millis();
+            let hashfull = self.transposition_table.hashfull();
+            let lines: Vec<String> = result
+                .lines
+                .iter()
+                .take(self.reported_lines)
+                .enumerate()
+                .map(|(index, line)| {
+                    let score = match line.score {
+                        Evaluation::Centipawns(centipawns) => format!("cp {centipawns}"),
+                        Evaluation::Mate(moves) => format!("mate {moves}"),
+                    };
+                    let movements: Vec<String> =
+                        line.movements.iter().map(BoardMovement::to_uci).collect();
+                    format!(
+                        "info depth {} multipv {} score {} nodes {} time {} hashfull {} pv {}",
+                        result.depth,
+                        index + 1,
+                        score,
+                        result.nodes,
+                        elapsed,
+                        hashfull,
+                        movements.join(" ")
+                    )
+                })
+                .collect();
+            write_lines(output, &lines);
+        }
+
+        while self.is_infinite && !self.stop_signal.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let line = self.search.result().and_then(|result| {
+            let movement = self
+                .skill_level
+                .choose_movement(&self.board, result, &mut self.rng)?;
+            result
+                .lines
+                .iter()
+                .find(|line| line.movements.first() == Some(&movement))
+        });
+        let best_move = match line.map(|line| line.movements.as_slice()) {
+            Some([best_move, ponder, ..]) => {
+                format!("bestmove {} ponder {}", best_move.to_uci(), ponder.to_uci())
+            }
+            Some([best_move]) => format!("bestmove {}", best_move.to_uci()),
+            _ => "bestmove 0000".to_string(),
+        };
+        write_lines(output, &[best_move]);
+    }
+}
+
+/// Get's the hashes of the positions played before the current one.
+fn get_history(game: &ChessGame) -> Vec<u64> {
+    let mut history = vec![game.initial_board().zobrist_hash()];
+    history.extend(game.history().iter().map(|record| record.hash));
+    history.pop();
+    history
+}
+
+/// Writes the lines at once, so the lines of a search are not mixed with other answers.
+fn write_lines<W: Write>(output: &Mutex<W>, lines: &[String]) {
+    let Ok(mut output) = output.lock() else {
+        return;
+    };
+    for line in lines {
+        let _ = writeln!(output, "{line}");
+    }
+    let _ = output.flush();
+}
@@ -0,0 +1,110 @@
+use super::*;
+
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Error, Serialize)]
+pub enum UciMoveError {
+    #[error("`{0}` is not a movement of the form `e2e4` or `e7e8q`.")]
+    InvalidFormat(String),
+    #[error("There's no piece on `{0}` to move.")]
+    EmptyOrigin(BoardPosition),
+}
+
+impl Board {
+    /// Get's the movement written in the coordinates UCI uses, like `e2e4`, `e1g1` to castle or
+    /// `e7e8q` to promote. The piece that moves is the one on the origin.
+    pub fn parse_uci_move(&self, text: &str) -> Result<BoardMovement, UciMoveError> {
+        let invalid_format = || UciMoveError::InvalidFormat(text.to_string());
+        let text = text.trim();
+
+        let destination_index = text
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_ascii_lowercase())
+            .map(|(index, _)| index)
+            .ok_or_else(invalid_format)?;
+        let (origin, destination) = text.split_at(destination_index);
+        let (destination, promotion) = match destination.char_indices().last() {
+            Some((index, symbol)) if index > 1 && symbol.is_ascii_lowercase() => (
+                &destination[..index],
+                Some(self.piece_kind(symbol).ok_or_else(invalid_format)?),
+            ),
+            _ => (destination, None),
+        };
+
+        let origin = BoardPosition::try_from(origin).map_err(|_| invalid_format())?;
+        let destination = BoardPosition::try_from(destination).map_err(|_| invalid_format())?;
+        let piece = self
+            .get_piece(&origin)
+            .ok_or(UciMoveError::EmptyOrigin(origin))?;
+
+        Ok(BoardMovement {
+            piece,
+            destination,
+            promotion,
+        })
+    }
+}
+
+impl BoardMovement {
+    /// Get's the movement in the coordinates UCI uses, like `e2e4` or `e7e8q`.
+    pub fn to_uci(&self) -> String {
+        let promotion = self
+            .promotion
+            .map(|kind| kind.symbol().to_ascii_lowercase().to_string())
+            .unwrap_or_default();
+
+        format!(
+            "{}{}{}",
+            self.piece.board_position(),
+            self.destination,
+            promotion
+        )
+    }
+}
+
+impl MoveRecord {
+    /// Get's the movement in the coordinates UCI uses, like `e2e4` or `e7e8q`.
+    pub fn to_uci(&self) -> String {
+        let promotion = self
+            .promotion
+            .map(|kind| kind.symbol().to_ascii_lowercase().to_string())
+            .unwrap_or_default();
+
+        format!("{}{}{}", self.from, self.to, promotion)
+    }
+}
+
+impl ChessGame {
+    /// Plays the movement written in UCI coordinates, like `e2e4` or `e7e8q`.
+    pub fn make_uci_move(&mut self, text: &str) -> Result<MoveRecord, GameError> {
+        let movement = self.board().parse_uci_move(text)?;
+        self.make_move(movement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promotions_are_read_with_the_pieces_of_the_board() {
+        let board = Fen::try_from("k7/4P3/8/8/8/8/8/K7 w - - 0 1")
+            .unwrap()
+            .board;
+        let movement = board.parse_uci_move("e7e8n").unwrap();
+        assert_eq!(movement.promotion, Some(PieceTypes::Knight));
+        assert!(matches!(
+            board.parse_uci_move("e7e8m"),
+            Err(UciMoveError::InvalidFormat(_))
+        ));
+
+        let board =
+            Fen::with_fairy_pieces("k7/4P3/8/8/8/8/8/K7 w - - 0 1", vec![FairyPiece::AMAZON])
+                .unwrap()
+                .board;
+        let movement = board.parse_uci_move("e7e8m").unwrap();
+        assert_eq!(movement.promotion, Some(PieceTypes::AMAZON));
+    }
+}
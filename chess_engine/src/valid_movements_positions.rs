@@ -1,44 +1,139 @@
 use super::*;
-use wasm_bindgen::prelude::*;
-
-#[wasm_bindgen]
-pub fn wasm_get_valid_movements_positions(
-    piece: JsValue,
-    board: JsValue,
-) -> Result<JsValue, JsValue> {
-    let piece: ChessPiece = serde_wasm_bindgen::from_value(piece)?;
-    let board: Board = serde_wasm_bindgen::from_value(board)?;
-    let positions = get_valid_movements_positions(&piece, &board);
-
-    Ok(serde_wasm_bindgen::to_value(&positions)?)
-}
 
 /// Get's all the valid positions this piece can move on the given board.
 pub fn get_valid_movements_positions(piece: &ChessPiece, board: &Board) -> Vec<BoardPosition> {
-    let mut pattern = get_movement_pattern(piece);
+    let mut positions = board.get_pseudo_legal_positions(piece);
+    positions.append(&mut get_castling_positions(piece, board));
 
-    if let PieceTypes::King = piece.kind() {
-        return pattern
-            .into_iter()
-            .flat_map(|path| path.0)
-            .filter(|pos| board.position_in_check(pos, piece.color()))
-            .collect();
+    positions
+        .into_iter()
+        .filter(|position| apply_movement(board, piece, position, None).is_ok())
+        .collect()
+}
+
+/// Get's all the valid movements the player of the given color can make, with a movement for
+/// each piece a pawn can promote to.
+pub fn get_legal_movements(board: &Board, color: &PieceColors) -> Vec<BoardMovement> {
+    get_candidate_movements(board, color)
+        .into_iter()
+        .filter(|movement| {
+            apply_movement(
+                board,
+                &movement.piece,
+                &movement.destination,
+                movement.promotion,
+            )
+            .is_ok()
+        })
+        .collect()
+}
+
+/// Get's the movements the player of the given color could make without considering checks.
+/// Pawns reaching the last rank get a movement for each piece they can promote to.
+pub(crate) fn get_candidate_movements(board: &Board, color: &PieceColors) -> Vec<BoardMovement> {
+    let mut movements = vec![];
+    for piece in board.get_pieces_from(color) {
+        let mut positions = board.get_pseudo_legal_positions(piece);
+        positions.append(&mut get_castling_positions(piece, board));
+
+        for destination in positions {
+            match get_promotion(board, piece, &destination, None) {
+                Ok(Some(_)) => {
+                    for promotion in get_promotion_kinds(board) {
+                        movements.push(BoardMovement {
+                            piece: piece.clone(),
+                            destination: destination.clone(),
+                            promotion: Some(promotion),
+                        });
+                    }
+                }
+                _ => movements.push(BoardMovement {
+                    piece: piece.clone(),
+                    destination,
+                    promotion: None,
+                }),
+            }
+        }
     }
 
-    let pawn_capture_pattern: Option<Vec<BoardPath>> = board.get_capture_pattern(piece);
-    if let (PieceTypes::Pawn, Some(mut paths)) = (piece.kind(), pawn_capture_pattern) {
-        pattern.append(&mut paths);
+    movements
+}
+
+/// Get's the positions the king would move to when castling to each side.
+fn get_castling_positions(piece: &ChessPiece, board: &Board) -> Vec<BoardPosition> {
+    let dimensions = board.dimensions();
+    if board.get_castling_state(piece.color()).is_none()
+        || !is_king_on_starting_position(piece, &dimensions)
+    {
+        return vec![];
     }
 
-    let paths: Vec<BoardPath> = board.colission_detection(pattern, piece);
-    let positions: Vec<BoardPosition> = paths.into_iter().flat_map(|path| path.0).collect();
+    let (row, _) = piece.position();
+    [ChessBoardDirections::Left, ChessBoardDirections::Right]
+        .iter()
+        .map(|direction| castling_files(direction, &dimensions).0)
+        .filter_map(|column| (row, column).try_into().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the positions reached after playing every sequence of `depth` valid movements.
+    fn perft(board: &Board, depth: usize) -> usize {
+        let movements = get_legal_movements(board, &board.side_to_move);
+        if depth == 1 {
+            return movements.len();
+        }
 
-    if let Some(check_positions) = board.get_check_positions() {
-        positions
+        movements
             .into_iter()
-            .filter(|pos| check_positions.contains(pos))
-            .collect()
-    } else {
-        positions
+            .map(|movement| {
+                let applied = apply_movement(
+                    board,
+                    &movement.piece,
+                    &movement.destination,
+                    movement.promotion,
+                )
+                .unwrap();
+                perft(&applied.board, depth - 1)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn perft_from_the_starting_position() {
+        let board = get_starting_board();
+        let nodes: Vec<usize> = (1..=4).map(|depth| perft(&board, depth)).collect();
+
+        assert_eq!(nodes, [20, 400, 8902, 197281]);
+    }
+
+    #[test]
+    fn perft_from_kiwipete() {
+        let castling = Some(CastlingState {
+            can_use_left_rook: true,
+            can_use_right_rook: true,
+        });
+        let board = BoardBuilder::from_diagram(
+            "\
+            r . . . k . . r
+            p . p p q p b .
+            b n . . p n p .
+            . . . P N . . .
+            . p . . P . . .
+            . . N . . Q . p
+            P P P B B P P P
+            R . . . K . . R",
+        )
+        .unwrap()
+        .castling(PieceColors::White, castling.clone())
+        .castling(PieceColors::Black, castling)
+        .build()
+        .unwrap();
+        let nodes: Vec<usize> = (1..=3).map(|depth| perft(&board, depth)).collect();
+
+        assert_eq!(nodes, [48, 2039, 97862]);
     }
 }
@@ -0,0 +1,390 @@
+use super::*;
+
+use rand::{rngs::SmallRng, SeedableRng};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// A board after a movement, with the record of the movement.
+#[derive(Serialize)]
+struct MovedBoard {
+    board: Board,
+    record: MoveRecord,
+}
+
+/// Whether the player that moves next is in check and how the game on the board stands.
+#[derive(Serialize)]
+struct BoardStatus {
+    in_check: bool,
+    status: GameStatus,
+}
+
+/// How far a step by step search is.
+#[derive(Serialize)]
+struct SearchProgress<'a> {
+    depth: usize,
+    nodes: u64,
+    is_finished: bool,
+    result: Option<&'a SearchResult>,
+}
+
+/// Reads a board sent from javascript, failing with its problems if it's not valid.
+fn read_board(board: JsValue) -> Result<Board, JsValue> {
+    let board: Board = serde_wasm_bindgen::from_value(board)?;
+    let problems = board.validate();
+    if !problems.is_empty() {
+        return Err(serde_wasm_bindgen::to_value(&problems)?);
+    }
+
+    Ok(board)
+}
+
+fn to_js_error(error: impl std::fmt::Display) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+#[wasm_bindgen]
+pub fn wasm_get_valid_movements_positions(
+    piece: JsValue,
+    board: JsValue,
+) -> Result<JsValue, JsValue> {
+    let piece: ChessPiece = serde_wasm_bindgen::from_value(piece)?;
+    let board = read_board(board)?;
+
+    let positions = get_valid_movements_positions(&piece, &board);
+
+    Ok(serde_wasm_bindgen::to_value(&positions)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_attacked_squares(board: JsValue, color: JsValue) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+    let color: PieceColors = serde_wasm_bindgen::from_value(color)?;
+
+    Ok(serde_wasm_bindgen::to_value(
+        &board.attacked_squares(&color),
+    )?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_attackers_of(
+    position: JsValue,
+    color: JsValue,
+    board: JsValue,
+) -> Result<JsValue, JsValue> {
+    let position: BoardPosition = serde_wasm_bindgen::from_value(position)?;
+    let color: PieceColors = serde_wasm_bindgen::from_value(color)?;
+    let board = read_board(board)?;
+
+    Ok(serde_wasm_bindgen::to_value(
+        &board.attackers_of(&position, &color),
+    )?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_get_legal_movements(board: JsValue, color: JsValue) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+    let color: PieceColors = serde_wasm_bindgen::from_value(color)?;
+
+    let movements = get_legal_movements(&board, &color);
+
+    Ok(serde_wasm_bindgen::to_value(&movements)?)
+}
+
+/// Moves a piece on a copy of the board, returning the moved board and the movement record.
+#[wasm_bindgen]
+pub fn wasm_move_piece(movement: JsValue, board: JsValue) -> Result<JsValue, JsValue> {
+    let movement: BoardMovement = serde_wasm_bindgen::from_value(movement)?;
+    let mut board = read_board(board)?;
+
+    let record = move_piece(movement, &mut board).map_err(to_js_error)?;
+
+    Ok(serde_wasm_bindgen::to_value(&MovedBoard { board, record })?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_get_san(movement: JsValue, board: JsValue) -> Result<String, JsValue> {
+    let movement: BoardMovement = serde_wasm_bindgen::from_value(movement)?;
+    let mut board = read_board(board)?;
+
+    let record = move_piece(movement, &mut board).map_err(to_js_error)?;
+
+    Ok(record.san)
+}
+
+#[wasm_bindgen]
+pub fn wasm_parse_san(san: &str, board: JsValue) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+
+    let movement = board.parse_san(san).map_err(to_js_error)?;
+
+    Ok(serde_wasm_bindgen::to_value(&movement)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_board_from_fen(fen: &str) -> Result<JsValue, JsValue> {
+    let fen = Fen::try_from(fen).map_err(to_js_error)?;
+
+    Ok(serde_wasm_bindgen::to_value(&fen.board)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_board_to_fen(board: JsValue) -> Result<String, JsValue> {
+    let board = read_board(board)?;
+
+    Ok(board.to_fen())
+}
+
+/// Reads every game of a PGN.
+#[wasm_bindgen]
+pub fn wasm_parse_pgn(pgn: &str) -> Result<JsValue, JsValue> {
+    let games = PgnGame::parse_all(pgn).map_err(to_js_error)?;
+
+    Ok(serde_wasm_bindgen::to_value(&games)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_write_pgn(game: JsValue) -> Result<String, JsValue> {
+    let game: PgnGame = serde_wasm_bindgen::from_value(game)?;
+
+    Ok(game.to_string())
+}
+
+#[wasm_bindgen]
+pub fn wasm_get_status(board: JsValue) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+
+    let status = BoardStatus {
+        in_check: board.is_in_check(&board.side_to_move),
+        status: board.status(),
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&status)?)
+}
+
+/// Searches the best movement for the player that moves next.
+/// The search can be limited by depth and nodes, but not by time, because there's no clock on
+/// web assembly.
+#[wasm_bindgen]
+pub fn wasm_search_best_move(board: JsValue, limits: JsValue) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+    let limits: SearchLimits = serde_wasm_bindgen::from_value(limits)?;
+    if limits.movetime.is_some() {
+        return Err(JsValue::from_str(
+            "The search can't be limited by time on web assembly.",
+        ));
+    }
+
+    let result = search_best_move(&board, limits);
+
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// Searches the best `lines` lines for the player that moves next, each starting with a
+/// different movement. They are on the `lines` of the result, from the best to the worst.
+#[wasm_bindgen]
+pub fn wasm_search_lines(
+    board: JsValue,
+    limits: JsValue,
+    lines: usize,
+) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+    let limits: SearchLimits = serde_wasm_bindgen::from_value(limits)?;
+    if limits.movetime.is_some() {
+        return Err(JsValue::from_str(
+            "The search can't be limited by time on web assembly.",
+        ));
+    }
+
+    let result = Search::new(board, limits).with_multi_pv(lines).run();
+
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// Chooses the movement to play at a skill level from 0 to 20, where 20 is the full strength.
+/// Weaker levels search less deep and make mistakes at random, so a `seed` for the randomness
+/// must be given, like `Date.now()`. Only the integer part of the seed is used.
+#[wasm_bindgen]
+pub fn wasm_search_skilled_move(
+    board: JsValue,
+    limits: JsValue,
+    skill_level: u8,
+    seed: f64,
+) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+    let limits: SearchLimits = serde_wasm_bindgen::from_value(limits)?;
+    if limits.movetime.is_some() {
+        return Err(JsValue::from_str(
+            "The search can't be limited by time on web assembly.",
+        ));
+    }
+
+    let mut rng = SmallRng::seed_from_u64(seed as u64);
+    let movement = search_skilled_move(&board, limits, &SkillLevel::new(skill_level), &mut rng);
+
+    Ok(serde_wasm_bindgen::to_value(&movement)?)
+}
+
+/// A search that runs a few positions at a time, so a web worker running it can answer messages
+/// between steps.
+#[wasm_bindgen]
+pub struct WasmSearch {
+    search: Search,
+    on_progress: Option<js_sys::Function>,
+}
+
+#[wasm_bindgen]
+impl WasmSearch {
+    /// Starts a search for the best movement of the player that moves next. As with
+    /// `wasm_search_best_move`, the search can't be limited by time.
+    #[wasm_bindgen(constructor)]
+    pub fn new(board: JsValue, limits: JsValue) -> Result<WasmSearch, JsValue> {
+        let board = read_board(board)?;
+        let limits: SearchLimits = serde_wasm_bindgen::from_value(limits)?;
+        if limits.movetime.is_some() {
+            return Err(JsValue::from_str(
+                "The search can't be limited by time on web assembly.",
+            ));
+        }
+
+        Ok(WasmSearch {
+            search: Search::new(board, limits),
+            on_progress: None,
+        })
+    }
+
+    /// Sets the FENs of the positions played before the searched one, to spot repetitions.
+    /// It fails once the search started stepping, since the searched lines would ignore it.
+    pub fn set_history(&mut self, history: JsValue) -> Result<(), JsValue> {
+        if self.search.depth() > 0 {
+            return Err(JsValue::from_str(
+                "The history must be set before the first step.",
+            ));
+        }
+
+        let fens: Vec<String> = serde_wasm_bindgen::from_value(history)?;
+        let history = fens
+            .iter()
+            .map(|fen| Fen::try_from(fen.as_str()).map(|fen| fen.board.zobrist_hash()))
+            .collect::<Result<Vec<u64>, FenError>>()
+            .map_err(to_js_error)?;
+
+        self.search = self.search.clone().with_history(history);
+        Ok(())
+    }
+
+    /// Sets how many lines, each starting with a different movement, the search looks for.
+    /// It fails once the search started stepping, like `set_history`.
+    pub fn set_multi_pv(&mut self, lines: usize) -> Result<(), JsValue> {
+        if self.search.depth() > 0 {
+            return Err(JsValue::from_str(
+                "The amount of lines must be set before the first step.",
+            ));
+        }
+
+        self.search = self.search.clone().with_multi_pv(lines);
+        Ok(())
+    }
+
+    /// Sets a function that's called with the result of every completed depth: the depth, the
+    /// score, the principal variation, the other lines and the nodes searched.
+    pub fn set_on_progress(&mut self, callback: js_sys::Function) {
+        self.on_progress = Some(callback);
+    }
+
+    /// Searches about `nodes` more positions, calling the progress function if a depth is
+    /// completed. Returns `true` while the search isn't finished.
+    pub fn step(&mut self, nodes: u32) -> Result<bool, JsValue> {
+        if let Some(result) = self.search.step(u64::from(nodes.max(1))) {
+            if let Some(callback) = &self.on_progress {
+                callback.call1(&JsValue::NULL, &serde_wasm_bindgen::to_value(result)?)?;
+            }
+        }
+
+        Ok(!self.search.is_finished())
+    }
+
+    /// Get's how far the search is, with the result of the deepest completed depth.
+    pub fn poll(&self) -> Result<JsValue, JsValue> {
+        let progress = SearchProgress {
+            depth: self.search.result().map_or(0, |result| result.depth),
+            nodes: self.search.nodes(),
+            is_finished: self.search.is_finished(),
+            result: self.search.result(),
+        };
+
+        Ok(serde_wasm_bindgen::to_value(&progress)?)
+    }
+
+    /// Stops the search, keeping the result of the deepest completed depth.
+    pub fn stop(&mut self) {
+        self.search.stop();
+    }
+}
+
+/// An opening book loaded once, so looking up positions doesn't read it again.
+#[wasm_bindgen]
+pub struct WasmOpeningBook {
+    book: OpeningBook,
+}
+
+#[wasm_bindgen]
+impl WasmOpeningBook {
+    /// Reads a book from the bytes of a Polyglot `.bin` file.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<WasmOpeningBook, JsValue> {
+        let book = OpeningBook::from_bytes(bytes).map_err(to_js_error)?;
+
+        Ok(WasmOpeningBook { book })
+    }
+
+    /// Get's the movements of the book for the position with their weights.
+    pub fn get_movements(&self, board: JsValue) -> Result<JsValue, JsValue> {
+        let board = read_board(board)?;
+
+        Ok(serde_wasm_bindgen::to_value(
+            &self.book.get_movements(&board),
+        )?)
+    }
+}
+
+/// Builds a Polyglot book from the first `max_ply` movements of the games of a PGN.
+#[wasm_bindgen]
+pub fn wasm_build_opening_book(pgn: &str, max_ply: usize) -> Result<Vec<u8>, JsValue> {
+    let games = PgnGame::parse_all(pgn).map_err(to_js_error)?;
+
+    let mut builder = OpeningBookBuilder::new(max_ply);
+    builder.add_games(&games);
+
+    Ok(builder.build().to_bytes())
+}
+
+/// Solves whether the player that moves next can force mate in at most `moves` movements,
+/// returning every key movement with the whole tree of defenses.
+#[wasm_bindgen]
+pub fn wasm_solve_mate(board: JsValue, moves: usize) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+
+    Ok(serde_wasm_bindgen::to_value(&solve_mate_in(&board, moves))?)
+}
+
+/// Solves whether the player that moves next can force its opponent to mate it in at most
+/// `moves` movements, returning every key movement with the whole tree of defenses.
+#[wasm_bindgen]
+pub fn wasm_solve_selfmate(board: JsValue, moves: usize) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+
+    Ok(serde_wasm_bindgen::to_value(&solve_selfmate_in(
+        &board, moves,
+    ))?)
+}
+
+/// Get's every line where both players cooperate so the player that moves next gets mated in at
+/// most `moves` movements.
+#[wasm_bindgen]
+pub fn wasm_solve_helpmate(board: JsValue, moves: usize) -> Result<JsValue, JsValue> {
+    let board = read_board(board)?;
+
+    Ok(serde_wasm_bindgen::to_value(&solve_helpmate_in(
+        &board, moves,
+    ))?)
+}
@@ -0,0 +1,62 @@
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use chess_engine::*;
+use serde::Serialize;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[wasm_bindgen_test]
+fn moves_a_piece_from_the_starting_position() {
+    let board = wasm_board_from_fen(STARTING_FEN).unwrap();
+    let movement = wasm_parse_san("e4", board.clone()).unwrap();
+
+    let moved = wasm_move_piece(movement, board).unwrap();
+
+    let moved: serde_json::Value = serde_wasm_bindgen::from_value(moved).unwrap();
+    assert_eq!(moved["record"]["san"], "e4");
+    assert_eq!(moved["record"]["hash"], "823c9b50fd114196");
+
+    let board = moved["board"]
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap();
+    assert_eq!(
+        wasm_board_to_fen(board).unwrap(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+}
+
+#[wasm_bindgen_test]
+fn search_history_is_read_from_fens() {
+    let board = wasm_board_from_fen(STARTING_FEN).unwrap();
+    let limits = serde_wasm_bindgen::to_value(&SearchLimits {
+        depth: Some(1),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut search = WasmSearch::new(board, limits).unwrap();
+
+    let invalid_history = serde_wasm_bindgen::to_value(&["not a fen"]).unwrap();
+    assert!(search.set_history(invalid_history).is_err());
+
+    let history = serde_wasm_bindgen::to_value(&[STARTING_FEN]).unwrap();
+    search.set_history(history.clone()).unwrap();
+    search.set_multi_pv(2).unwrap();
+    while search.step(1000).unwrap() {}
+    assert!(search.set_history(history).is_err());
+    assert!(search.set_multi_pv(3).is_err());
+}
+
+#[wasm_bindgen_test]
+fn skilled_move_is_seeded_with_the_current_time() {
+    let board = wasm_board_from_fen(STARTING_FEN).unwrap();
+    let limits = serde_wasm_bindgen::to_value(&SearchLimits {
+        depth: Some(2),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let movement = wasm_search_skilled_move(board, limits, 5, js_sys::Date::now()).unwrap();
+
+    assert!(movement.is_object());
+}
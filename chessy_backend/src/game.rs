@@ -1,12 +1,16 @@
 use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use actix::Recipient;
-use chess_engine::{get_starting_board, Board, PieceColors};
+use chess_engine::{get_starting_board, Board, ChessGame, FogOfWarView, GameVariant, PieceColors};
 use serde::Serialize;
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::{player::Player, websocket::GameMessage};
+use crate::{
+    handicap::{Handicap, HandicapError},
+    player::Player,
+    websocket::GameMessage,
+};
 
 type Client = Recipient<GameMessage>;
 
@@ -28,9 +32,9 @@ impl ServerGame {
         name: Arc<str>,
         client: Recipient<GameMessage>,
     ) -> Result<Player, AddOpponentErrors> {
-        self.game.add_opponent(client_id, name).and_then(|player| {
+        self.game.add_opponent(client_id, name).map(|player| {
             self.sessions.insert(client_id, client);
-            Ok(player)
+            player
         })
     }
 
@@ -63,8 +67,24 @@ impl ServerGame {
 #[derive(Debug, Serialize, Clone)]
 pub struct Game {
     pub players: HashMap<PieceColors, Player>,
-    pub board: Board,
+    pub chess: ChessGame,
     pub initial_ms_per_player: u128,
+    pub handicap: Option<Handicap>,
+}
+
+/// Represents a Fog of War Chess Game as seen by one of the players.
+#[derive(Debug, Serialize, Clone)]
+pub struct FogOfWarGame {
+    pub players: HashMap<PieceColors, Player>,
+    pub board: FogOfWarView,
+    pub initial_ms_per_player: u128,
+}
+
+/// The data of a game a player is allowed to see.
+#[derive(Debug, Serialize, Clone)]
+pub enum GameView {
+    Standard(Box<Game>),
+    FogOfWar(FogOfWarGame),
 }
 
 /// Configuration to start a Chess Game.
@@ -73,15 +93,23 @@ pub struct GameConfig {
     players_names: HashMap<PieceColors, (Uuid, Arc<str>)>,
     ms_per_player: u128,
     board: Board,
+    variant: GameVariant,
+    handicap: Option<Handicap>,
 }
 
 impl GameConfig {
-    pub fn new(players_names: HashMap<PieceColors, (Uuid, Arc<str>)>, ms_per_player: u128) -> Self {
+    pub fn new(
+        players_names: HashMap<PieceColors, (Uuid, Arc<str>)>,
+        ms_per_player: u128,
+        variant: GameVariant,
+    ) -> Self {
         let board = get_starting_board();
         GameConfig {
             players_names,
             ms_per_player,
             board,
+            variant,
+            handicap: None,
         }
     }
 
@@ -94,8 +122,25 @@ impl GameConfig {
             players_names,
             ms_per_player,
             board,
+            variant: GameVariant::Standard,
+            handicap: None,
         }
     }
+
+    /// Creates the configuration of a game of the given variant where one player gives material
+    /// and/or time odds.
+    pub fn new_with_handicap(
+        players_names: HashMap<PieceColors, (Uuid, Arc<str>)>,
+        handicap: Handicap,
+        variant: GameVariant,
+    ) -> Result<Self, HandicapError> {
+        let board = handicap.board()?;
+        Ok(GameConfig {
+            variant,
+            handicap: Some(handicap.clone()),
+            ..Self::new_with_board(players_names, handicap.receiver_ms, board)
+        })
+    }
 }
 
 impl Game {
@@ -104,22 +149,50 @@ impl Game {
             players_names,
             ms_per_player,
             board,
+            variant,
+            handicap,
         }: GameConfig,
     ) -> Self {
         let players = players_names
             .into_iter()
-            .map(|(color, (id, name))| (color.clone(), Player::new(id, name, color, ms_per_player)))
+            .map(|(color, (id, name))| {
+                let ms_remaining = handicap
+                    .as_ref()
+                    .map_or(ms_per_player, |handicap| handicap.ms_for(&color));
+                (color.clone(), Player::new(id, name, color, ms_remaining))
+            })
             .collect();
 
         let initial_ms_per_player = ms_per_player;
 
         Game {
             players,
-            board,
+            chess: ChessGame::new(board, variant),
             initial_ms_per_player,
+            handicap,
         }
     }
 
+    /// Get's the data of this game the player with the given color is allowed to see.
+    pub fn view_for(&self, color: &PieceColors) -> GameView {
+        match self.chess.variant() {
+            GameVariant::Standard => GameView::Standard(Box::new(self.clone())),
+            GameVariant::FogOfWar => GameView::FogOfWar(FogOfWarGame {
+                players: self.players.clone(),
+                board: FogOfWarView::new(self.chess.board(), color),
+                initial_ms_per_player: self.initial_ms_per_player,
+            }),
+        }
+    }
+
+    /// Get's the color the player with the given id is playing with.
+    pub fn color_of(&self, client_id: &Uuid) -> Option<PieceColors> {
+        self.players
+            .values()
+            .find(|player| player.id() == client_id)
+            .map(|player| player.color().clone())
+    }
+
     pub fn add_opponent(
         &mut self,
         client_id: Uuid,
@@ -129,14 +202,20 @@ impl Game {
             .players
             .iter()
             .next()
-            .ok_or_else(|| AddOpponentErrors::NoPlayer1Found)?;
+            .ok_or(AddOpponentErrors::NoPlayer1Found)?;
         let color = color.opponent();
 
         Ok(self.add_player(client_id, name, color))
     }
 
     fn add_player(&mut self, client_id: Uuid, name: Arc<str>, color: PieceColors) -> Player {
-        let player = Player::new(client_id, name, color.clone(), self.initial_ms_per_player);
+        let ms_remaining = self
+            .handicap
+            .as_ref()
+            .map_or(self.initial_ms_per_player, |handicap| {
+                handicap.ms_for(&color)
+            });
+        let player = Player::new(client_id, name, color.clone(), ms_remaining);
         self.players.insert(color, player.clone());
 
         player
@@ -0,0 +1,92 @@
+use chess_engine::{get_starting_board, Board, BoardPosition, PieceColors};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The material a player gives away at the start of the game.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MaterialOdds {
+    /// The queen side knight is removed.
+    Knight,
+    /// The queen side rook is removed, along with the castling to that side.
+    Rook,
+    /// The queen is removed.
+    Queen,
+    /// The f pawn is removed and the opponent moves first.
+    PawnAndMove,
+}
+
+/// The odds a player gives to his opponent.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Handicap {
+    /// The color of the player that gives the odds.
+    pub giver: PieceColors,
+    pub material: Option<MaterialOdds>,
+    /// The milliseconds the player giving the odds starts with.
+    pub giver_ms: u128,
+    /// The milliseconds the player receiving the odds starts with.
+    pub receiver_ms: u128,
+}
+
+/// The settings a client sends to create a game with odds.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HandicapSettings {
+    /// The color the player creating the game wants to play with.
+    pub color: PieceColors,
+    pub handicap: Handicap,
+}
+
+#[derive(Debug, Clone, Error, Serialize)]
+pub enum HandicapError {
+    #[error("The odds position `{0}` is not on the board.")]
+    InvalidOddsPosition(String),
+    #[error("There's no piece to give away on `{0}`.")]
+    MissingOddsPiece(String),
+    #[error("Both players must start with some time on the clock.")]
+    NoTimeOnClock,
+}
+
+impl Handicap {
+    /// Get's the milliseconds the player with the given color starts with.
+    pub fn ms_for(&self, color: &PieceColors) -> u128 {
+        if color == &self.giver {
+            self.giver_ms
+        } else {
+            self.receiver_ms
+        }
+    }
+
+    /// Get's the starting board without the material given away.
+    pub fn board(&self) -> Result<Board, HandicapError> {
+        if self.giver_ms == 0 || self.receiver_ms == 0 {
+            return Err(HandicapError::NoTimeOnClock);
+        }
+
+        let mut board = get_starting_board();
+
+        if let Some(material) = self.material {
+            let (file, rank) = match material {
+                MaterialOdds::Knight => ('b', 1),
+                MaterialOdds::Rook => ('a', 1),
+                MaterialOdds::Queen => ('d', 1),
+                MaterialOdds::PawnAndMove => ('f', 2),
+            };
+            let rank = match self.giver {
+                PieceColors::White => rank,
+                PieceColors::Black => 9 - rank,
+            };
+
+            let square = format!("{}{}", file, rank);
+            let position = BoardPosition::try_from(square.as_str())
+                .map_err(|_| HandicapError::InvalidOddsPosition(square.clone()))?;
+            if board.remove_piece(&position).is_none() {
+                return Err(HandicapError::MissingOddsPiece(square));
+            }
+
+            if let MaterialOdds::PawnAndMove = material {
+                board.side_to_move = self.giver.opponent();
+            }
+        }
+
+        Ok(board)
+    }
+}
@@ -1,6 +1,3 @@
-#[macro_use]
-extern crate log;
-
 use std::env;
 use std::sync::Mutex;
 use std::{collections::HashMap, sync::Arc};
@@ -11,6 +8,7 @@ use dotenv::dotenv;
 use uuid::Uuid;
 
 mod game;
+mod handicap;
 mod player;
 mod routes;
 mod websocket;
@@ -29,7 +27,7 @@ async fn main() -> std::io::Result<()> {
     let bind_host = "0.0.0.0:8080";
 
     HttpServer::new(move || {
-        let cors = Cors::default()
+        let _cors = Cors::default()
             .allowed_origin(
                 &env::var("CLIENT_HOST").expect("No `CLIENT_HOST` on environment variables!"),
             )
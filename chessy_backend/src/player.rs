@@ -33,6 +33,14 @@ impl Player {
         &self.id
     }
 
+    pub fn color(&self) -> &PieceColors {
+        &self.color
+    }
+
+    pub fn ms_remaining(&self) -> u128 {
+        self.ms_remaining
+    }
+
     pub(crate) fn reduce_time_by(&mut self, delta_ms: u128) -> PlayerTimeChanged {
         match self.ms_remaining.cmp(&delta_ms) {
             std::cmp::Ordering::Greater => {
@@ -1,4 +1,4 @@
-use std::{fmt::Display, sync::Arc};
+use std::sync::Arc;
 
 use actix::{fut, prelude::*};
 use actix_broker::BrokerIssue;
@@ -6,14 +6,15 @@ use actix_web::{
     http::header::ContentType, web, Error, HttpRequest, HttpResponse, Responder, ResponseError,
 };
 use actix_web_actors::ws;
-use chess_engine::BoardMovement;
+use chess_engine::{BoardMovement, GameVariant};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    handicap::HandicapSettings,
     websocket::{
         ChessServer, CreateGame, GameMessage, JoinGame, JoinedGameResponses, LeaveGame,
-        SendMovement,
+        MovementRequest, SendMovement,
     },
     AppState,
 };
@@ -99,13 +100,20 @@ impl WsChatSession {
             .wait(ctx);
     }
 
-    pub fn create_game(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+    pub fn create_game(
+        &mut self,
+        variant: GameVariant,
+        handicap: Option<HandicapSettings>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
         let client = ctx.address().recipient();
         let client_id = self.id;
         let msg = CreateGame {
             client_id,
             client,
             name: self.username.clone(),
+            variant,
+            handicap,
         };
 
         // Leave the current game if there is one
@@ -115,8 +123,8 @@ impl WsChatSession {
             .send(msg)
             .into_actor(self)
             .then(move |result, actor, _| {
-                if let Ok(game_id) = result {
-                    actor.id = client_id.clone();
+                if let Ok(Ok(game_id)) = result {
+                    actor.id = client_id;
                     actor.game_id = Some(game_id);
                 }
 
@@ -128,18 +136,15 @@ impl WsChatSession {
     /// Tries to leave a game if we're connected to one.
     pub fn leave_game(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
         let client_id = self.id;
-        match self.game_id {
-            Some(game_id) => {
-                let msg = LeaveGame { game_id, client_id };
-                self.issue_system_sync(msg, ctx);
-            }
-            None => {}
+        if let Some(game_id) = self.game_id {
+            let msg = LeaveGame { game_id, client_id };
+            self.issue_system_sync(msg, ctx);
         }
     }
 
     fn make_movement(
         &self,
-        movement: BoardMovement,
+        movement: MovementRequest,
         ctx: &mut ws::WebsocketContext<WsChatSession>,
     ) {
         let client_id = self.id;
@@ -207,10 +212,23 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsChatSession {
 
                 match serde_json::from_str(msg) {
                     Ok(result) => match result {
-                        WsSessionMessage::CreateGame => self.create_game(ctx),
+                        WsSessionMessage::CreateGame => {
+                            self.create_game(GameVariant::Standard, None, ctx)
+                        }
+                        WsSessionMessage::CreateVariantGame(variant) => {
+                            self.create_game(variant, None, ctx)
+                        }
+                        WsSessionMessage::CreateHandicapGame(settings) => {
+                            self.create_game(GameVariant::Standard, Some(settings), ctx)
+                        }
                         WsSessionMessage::JoinGame(ids) => self.join_game(ids, ctx),
                         WsSessionMessage::LeaveGame => self.leave_game(ctx),
-                        WsSessionMessage::Movement(movement) => self.make_movement(movement, ctx),
+                        WsSessionMessage::Movement(movement) => {
+                            self.make_movement(MovementRequest::Piece(movement), ctx)
+                        }
+                        WsSessionMessage::UciMovement(text) => {
+                            self.make_movement(MovementRequest::Uci(text), ctx)
+                        }
                     },
                     Err(_) => {
                         ctx.text(WsSessionErrors::InvalidJSONRequest.to_string());
@@ -236,9 +254,13 @@ pub struct ClientAndGameId {
 #[derive(Debug, Deserialize, Serialize)]
 enum WsSessionMessage {
     CreateGame,
+    CreateVariantGame(GameVariant),
+    CreateHandicapGame(HandicapSettings),
     JoinGame(ClientAndGameId),
     LeaveGame,
     Movement(BoardMovement),
+    /// A movement in UCI coordinates, like `e2e4` or `e7e8q`.
+    UciMovement(String),
 }
 
 #[derive(Debug, thiserror::Error)]
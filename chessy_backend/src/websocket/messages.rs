@@ -1,17 +1,23 @@
 use std::sync::Arc;
 
 use actix::prelude::*;
-use chess_engine::{BoardMovement, MovementError};
+use chess_engine::{
+    BoardMovement, DrawReason, GameError, GameStatus, GameVariant, MoveRecord, PieceColors,
+};
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::{game::Game, player::Player};
+use crate::{
+    game::{Game, GameView},
+    handicap::{HandicapError, HandicapSettings},
+};
 
 type Client = Recipient<GameMessage>;
 
 #[derive(Serialize, Debug)]
 pub enum WinReasons {
-    Checkmate(Game),
+    Checkmate(Box<Game>),
+    KingCaptured(Box<Game>),
     OpponentSurrenders,
     OpponentLostOnTime,
     OpponentDisconnected,
@@ -19,15 +25,18 @@ pub enum WinReasons {
 
 #[derive(Serialize, Debug)]
 pub enum LooseReasons {
-    Checkmate(Game),
+    Checkmate(Box<Game>),
+    KingCaptured(Box<Game>),
     NoTimeLeft,
     YouSurrendered,
 }
 
 #[derive(Serialize, Debug)]
 pub enum DrawReasons {
-    Stalemate(Game),
+    Stalemate(Box<Game>),
     Agreement,
+    /// The game ended in a draw by repetition, the move rules or insufficient material.
+    Rule(DrawReason, Box<Game>),
 }
 
 #[derive(Serialize, Debug)]
@@ -37,6 +46,56 @@ pub enum GameEndedReason {
     Draw(DrawReasons),
 }
 
+impl GameEndedReason {
+    /// Get's why the game ended for the player of the given color, if it ended.
+    pub fn for_player(game: &Game, color: &PieceColors) -> Option<Self> {
+        let reason = match game.chess.status() {
+            GameStatus::Ongoing => return None,
+            GameStatus::Checkmate { winner } if winner == color => {
+                GameEndedReason::YouWin(WinReasons::Checkmate(Box::new(game.clone())))
+            }
+            GameStatus::Checkmate { .. } => {
+                GameEndedReason::YouLoose(LooseReasons::Checkmate(Box::new(game.clone())))
+            }
+            GameStatus::KingCaptured { winner } if winner == color => {
+                GameEndedReason::YouWin(WinReasons::KingCaptured(Box::new(game.clone())))
+            }
+            GameStatus::KingCaptured { .. } => {
+                GameEndedReason::YouLoose(LooseReasons::KingCaptured(Box::new(game.clone())))
+            }
+            GameStatus::Resignation { winner } if winner == color => {
+                GameEndedReason::YouWin(WinReasons::OpponentSurrenders)
+            }
+            GameStatus::Resignation { .. } => {
+                GameEndedReason::YouLoose(LooseReasons::YouSurrendered)
+            }
+            GameStatus::Timeout { winner } if winner == color => {
+                GameEndedReason::YouWin(WinReasons::OpponentLostOnTime)
+            }
+            GameStatus::Timeout { .. } => GameEndedReason::YouLoose(LooseReasons::NoTimeLeft),
+            GameStatus::Stalemate => {
+                GameEndedReason::Draw(DrawReasons::Stalemate(Box::new(game.clone())))
+            }
+            GameStatus::Draw(DrawReason::Agreement) => {
+                GameEndedReason::Draw(DrawReasons::Agreement)
+            }
+            GameStatus::Draw(reason) => {
+                GameEndedReason::Draw(DrawReasons::Rule(*reason, Box::new(game.clone())))
+            }
+        };
+
+        Some(reason)
+    }
+}
+
+/// A movement played on a game.
+#[derive(Serialize, Debug)]
+pub struct MoveEvent {
+    pub record: MoveRecord,
+    /// The milliseconds the player that moved has left.
+    pub ms_remaining: u128,
+}
+
 /// Server sends this message to sessions
 #[derive(Message, Serialize, Debug)]
 #[rtype(result = "()")]
@@ -44,23 +103,31 @@ pub enum GameMessage {
     /// The server responds with this message when the game is created.
     /// The response is the game UUID.
     GameCreated(Uuid),
+    /// The server responds with this message when the game couldn't be created.
+    /// The response is why the game couldn't be created.
+    GameNotCreated(HandicapError),
     /// The server responds with this message when a player joined.
-    /// The response is all the necessary data to display a game.
-    PlayerJoined(Game),
+    /// The response is all the necessary data the player can see to display a game.
+    PlayerJoined(GameView),
     ///The server responds with this message when a board movement was played.
     ///The response is a result, that can either be `Ok or `Err`.
-    BoardMovement(Result<Game, MovementError>),
+    BoardMovement(Result<GameView, GameError>),
+    /// The server sends this message instead of the whole game when a movement was played on a
+    /// game where both players see the whole board.
+    PieceMoved(MoveEvent),
     /// The server responds with this message when a game has reached a state where it ends.
     /// The response is the reason it ended.
     GameEnded(GameEndedReason),
 }
 
 #[derive(Debug, Clone, Message)]
-#[rtype(result = "Uuid")]
+#[rtype(result = "Result<Uuid, HandicapError>")]
 pub struct CreateGame {
     pub client_id: Uuid,
     pub name: Arc<str>,
     pub client: Client,
+    pub variant: GameVariant,
+    pub handicap: Option<HandicapSettings>,
 }
 
 #[derive(Debug)]
@@ -89,10 +156,20 @@ pub struct LeaveGame {
     pub client_id: Uuid,
 }
 
+/// A movement as a client can send it.
+#[derive(Clone, Debug)]
+pub enum MovementRequest {
+    /// The piece to move and its destination, as the client sees them.
+    Piece(BoardMovement),
+    /// The movement in UCI coordinates, like `e2e4` or `e7e8q`. The piece that moves is the one
+    /// on the origin of the server board.
+    Uci(String),
+}
+
 #[derive(Clone, Message)]
 #[rtype(result = "()")]
 pub struct SendMovement {
     pub game_id: Uuid,
     pub client_id: Uuid,
-    pub movement: BoardMovement,
+    pub movement: MovementRequest,
 }
@@ -2,8 +2,8 @@ use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use actix::prelude::*;
 use actix_broker::BrokerSubscribe;
-use chess_engine::{BoardMovement, PieceColors};
-use rand::{rngs::ThreadRng, Rng, thread_rng};
+use chess_engine::{GameError, GameVariant, MovementError, PieceColors};
+use rand::{rngs::ThreadRng, thread_rng, Rng};
 use uuid::Uuid;
 
 use crate::{
@@ -11,7 +11,10 @@ use crate::{
     websocket::GameEndedReason,
 };
 
-use super::{CreateGame, GameMessage, JoinGame, JoinedGameResponses, LeaveGame, SendMovement};
+use super::{
+    CreateGame, GameMessage, JoinGame, JoinedGameResponses, LeaveGame, MoveEvent, MovementRequest,
+    SendMovement,
+};
 
 #[derive(Default)]
 pub struct ChessServer {
@@ -55,7 +58,7 @@ impl ChessServer {
         &mut self,
         client_id: &Uuid,
         game_id: &Uuid,
-        movement: chess_engine::BoardMovement,
+        movement: MovementRequest,
         instant: Instant,
         ctx: &mut Context<ChessServer>,
     ) {
@@ -65,107 +68,97 @@ impl ChessServer {
             last_move,
         }) = self.games.get_mut(game_id)
         {
-            let Game { players, board, .. } = game;
-            let BoardMovement { piece, .. } = movement.clone();
-            let player = match players.get_mut(piece.color()) {
+            // Only the clock of the player whose turn it is runs.
+            let color = game.chess.board().side_to_move.clone();
+            let player = match game.players.get_mut(&color) {
                 Some(p) => p,
                 None => {
                     self.end_game_because_player_leaved(game_id, client_id, ctx);
                     return;
                 }
             };
-            let result = chess_engine::move_piece(movement, board);
-
-            match result {
-                Ok(success_type) => {
-                    let delta_ms = instant.duration_since(*last_move).as_millis();
-                    let time_changed = player.reduce_time_by(delta_ms);
-                    match time_changed {
-                        crate::player::PlayerTimeChanged::InsufficientTime => {
-                            sessions
-                                .iter_mut()
-                                .map(|(id, client)| {
-                                    if id == client_id {
-                                        client.try_send(GameMessage::GameEnded(
-                                            GameEndedReason::YouLoose(
-                                                super::LooseReasons::NoTimeLeft,
-                                            ),
-                                        ))
-                                    } else {
-                                        client.try_send(GameMessage::GameEnded(
-                                            GameEndedReason::YouWin(
-                                                super::WinReasons::OpponentLostOnTime,
-                                            ),
-                                        ))
-                                    }
-                                })
-                                .for_each(|result| {
-                                    log::debug!("Message sent with result: {:?}", result)
-                                });
-                        }
-                        crate::player::PlayerTimeChanged::LastMove
-                        | crate::player::PlayerTimeChanged::TimeRemaining => match success_type {
-                            chess_engine::MovementSuccess::NormalMovement => {
-                                sessions
+            // Only the player whose turn it is can move.
+            if player.id() != client_id {
+                log::debug!(
+                    "Client ({}) tried to move on game ({}) out of turn",
+                    client_id,
+                    game_id
+                );
+                if let Some(client) = sessions.get(client_id) {
+                    let error = GameError::Movement(MovementError::NotYourTurn);
+                    let _ = client.try_send(GameMessage::BoardMovement(Err(error)));
+                }
+                return;
+            }
+            let delta_ms = instant.duration_since(*last_move).as_millis();
+
+            if player.ms_remaining() < delta_ms {
+                player.reduce_time_by(delta_ms);
+                if let Err(error) = game.chess.timeout(&color) {
+                    log::debug!("Couldn't end game ({}) on time: {}", game_id, error);
+                    return;
+                }
+            } else {
+                let result = match movement {
+                    MovementRequest::Piece(movement) => game.chess.make_move(movement),
+                    MovementRequest::Uci(text) => game.chess.make_uci_move(&text),
+                };
+                match result {
+                    Ok(record) => {
+                        player.reduce_time_by(delta_ms);
+                        let ms_remaining = player.ms_remaining();
+                        *last_move = instant;
+
+                        if !game.chess.status().is_over() {
+                            match game.chess.variant() {
+                                GameVariant::Standard => sessions
                                     .iter_mut()
-                                    .map(move |(_, client)| {
-                                        client
-                                            .try_send(GameMessage::BoardMovement(Ok(game.clone())))
+                                    .map(|(_, client)| {
+                                        client.try_send(GameMessage::PieceMoved(MoveEvent {
+                                            record: record.clone(),
+                                            ms_remaining,
+                                        }))
                                     })
                                     .for_each(|result| {
                                         log::debug!("Message sent with result: {:?}", result)
-                                    });
-                            }
-                            chess_engine::MovementSuccess::CheckmateMovement => {
-                                sessions
+                                    }),
+                                GameVariant::FogOfWar => sessions
                                     .iter_mut()
-                                    .map(|(id, client)| {
-                                        if id == client_id {
-                                            client.try_send(GameMessage::GameEnded(
-                                                GameEndedReason::YouWin(
-                                                    super::WinReasons::Checkmate(game.clone()),
-                                                ),
-                                            ))
-                                        } else {
-                                            client.try_send(GameMessage::GameEnded(
-                                                GameEndedReason::YouLoose(
-                                                    super::LooseReasons::Checkmate(game.clone()),
-                                                ),
-                                            ))
-                                        }
+                                    .filter_map(|(id, client)| {
+                                        let view = game.view_for(&game.color_of(id)?);
+                                        Some(client.try_send(GameMessage::BoardMovement(Ok(view))))
                                     })
                                     .for_each(|result| {
                                         log::debug!("Message sent with result: {:?}", result)
-                                    });
+                                    }),
                             }
-                            chess_engine::MovementSuccess::StalemateMovement => {
-                                sessions
-                                    .iter_mut()
-                                    .map(|(_, client)| {
-                                        client.try_send(GameMessage::GameEnded(
-                                            GameEndedReason::Draw(super::DrawReasons::Stalemate(
-                                                game.clone(),
-                                            )),
-                                        ))
-                                    })
-                                    .for_each(|result| {
-                                        log::debug!("Message sent with result: {:?}", result)
-                                    });
+                            return;
+                        }
+                    }
+                    Err(error_type) => {
+                        match sessions.iter_mut().find(|(id, _)| id == &client_id) {
+                            Some((id, client)) => {
+                                log::debug!("Movement error ({}) on client ({})", error_type, id);
+                                let _ =
+                                    client.try_send(GameMessage::BoardMovement(Err(error_type)));
                             }
-                        },
+                            None => log::debug!(
+                                "No client with id ({}) to send the movement error found!",
+                                client_id
+                            ),
+                        }
+                        return;
                     }
                 }
-                Err(error_type) => match sessions.iter_mut().find(|(id, _)| id == &client_id) {
-                    Some((id, client)) => {
-                        log::debug!("Movement error ({}) on client ({})", error_type, id);
-                        let _ = client.try_send(GameMessage::BoardMovement(Err(error_type)));
-                    }
-                    None => log::debug!(
-                        "No client with id ({}) to send the movement error found!",
-                        client_id
-                    ),
-                },
             }
+
+            sessions
+                .iter_mut()
+                .filter_map(|(id, client)| {
+                    let reason = GameEndedReason::for_player(game, &game.color_of(id)?)?;
+                    Some(client.try_send(GameMessage::GameEnded(reason)))
+                })
+                .for_each(|result| log::debug!("Message sent with result: {:?}", result));
         }
     }
 
@@ -177,20 +170,26 @@ impl ChessServer {
         client: Recipient<GameMessage>,
         _ctx: &mut Context<ChessServer>,
     ) -> JoinedGameResponses {
-
         match self.games.get_mut(&game_id) {
             Some(game) => {
                 if game.is_full() {
                     JoinedGameResponses::GameFull
                 } else {
-                    if let Err(_) = game.add_opponent(client_id, client_name.clone(), client.clone()){
+                    if game
+                        .add_opponent(client_id, client_name.clone(), client.clone())
+                        .is_err()
+                    {
                         let mut rng = thread_rng();
                         game.add_player(client_id, client_name, client, get_random_color(&mut rng));
                     }
                     game.update_last_move();
 
-                    game.sessions.iter_mut().for_each(|(_, client)| {
-                        let _ = client.try_send(GameMessage::PlayerJoined(game.game.clone()));
+                    let ServerGame { game, sessions, .. } = game;
+                    sessions.iter_mut().for_each(|(id, client)| {
+                        if let Some(color) = game.color_of(id) {
+                            let _ =
+                                client.try_send(GameMessage::PlayerJoined(game.view_for(&color)));
+                        }
                     });
 
                     JoinedGameResponses::JoinedGame
@@ -212,11 +211,13 @@ impl Actor for ChessServer {
 impl Handler<CreateGame> for ChessServer {
     type Result = MessageResult<CreateGame>;
 
-    fn handle(&mut self, msg: CreateGame, ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, msg: CreateGame, _ctx: &mut Self::Context) -> Self::Result {
         let CreateGame {
             client_id,
             client,
             name,
+            variant,
+            handicap,
         } = msg;
         let ChessServer { games, rng } = self;
 
@@ -226,7 +227,9 @@ impl Handler<CreateGame> for ChessServer {
             client_id,
             game_id
         );
-        let color = get_random_color(rng);
+        let color = handicap
+            .as_ref()
+            .map_or_else(|| get_random_color(rng), |settings| settings.color.clone());
         log::debug!(
             "Client ID (`{}`) identified as `{}` and will play `{}`",
             client_id,
@@ -235,18 +238,36 @@ impl Handler<CreateGame> for ChessServer {
         );
 
         let players = HashMap::from([(color, (client_id, name))]);
-        let game_config = GameConfig::new(players, 10 * 60 * 1000);
+        let game_config = match handicap {
+            Some(settings) => GameConfig::new_with_handicap(players, settings.handicap, variant),
+            None => Ok(GameConfig::new(players, 10 * 60 * 1000, variant)),
+        };
+        let game_config = match game_config {
+            Ok(game_config) => game_config,
+            Err(error) => {
+                log::debug!(
+                    "Client ID (`{}`) couldn't create the game: {}",
+                    client_id,
+                    error
+                );
+                let _ = client.try_send(GameMessage::GameNotCreated(error.clone()));
+                return MessageResult(Err(error));
+            }
+        };
         let game = Game::new(game_config);
 
         let msg = GameMessage::GameCreated(game_id);
-        client
-            .try_send(msg)
-            .expect("Coudn't send the created game message to client!");
+        if let Err(error) = client.try_send(msg) {
+            log::debug!(
+                "Couldn't send the created game message to client: {}",
+                error
+            );
+        }
 
         let sessions = HashMap::from([(client_id, client)]);
-        games.insert(game_id.clone(), ServerGame::new(game, sessions));
+        games.insert(game_id, ServerGame::new(game, sessions));
 
-        MessageResult(game_id)
+        MessageResult(Ok(game_id))
     }
 }
 